@@ -0,0 +1,54 @@
+/*
+This example requires the `argh` and `stream` features.
+
+Run it with:
+```bash
+cargo run -q -F=argh --example argh
+```
+
+Try running it:
+- with/without the NO_COLOR, CLICOLOR, CLICOLOR_FORCE environment variables
+- with/without the `--color {always,auto,never}` option
+- piping the stdout `| cat` or piping both stdout/stderr `|& cat`
+
+For example:
+```bash
+CLICOLOR_FORCE=1 cargo run -q -F=argh --example argh -- --color never | cat
+```
+*/
+
+use argh::FromArgs;
+use should_color::{resolve, ColorChoice};
+
+#[derive(Debug, FromArgs)]
+/// Demonstrate the color resolution with argh.
+struct Cli {
+    /// coloring (always, auto, never)
+    #[argh(option, arg_name = "WHEN")]
+    color: Option<ColorChoice>,
+}
+
+fn main() {
+    let cli: Cli = argh::from_env();
+
+    // resolve from cli preference, environment variables, default value
+    let color_choice = resolve(cli.color).unwrap_or(ColorChoice::Auto);
+
+    let color_stdout = color_choice.for_stream(atty::Stream::Stdout);
+    let color_stderr = color_choice.for_stream(atty::Stream::Stderr);
+
+    println!("         cli = {cli:?}");
+    println!("color_choice = {color_choice:?}");
+
+    if color_stdout {
+        println!("\x1b[3;92mColorize stdout\x1b[0m: \x1b[93m{color_stdout}\x1b[0m");
+    } else {
+        println!("Colorize stdout: {color_stdout}");
+    }
+
+    if color_stderr {
+        eprintln!("\x1b[4;91mColorize stderr\x1b[0m: \x1b[93m{color_stderr}\x1b[0m");
+    } else {
+        eprintln!("Colorize stderr: {color_stderr}");
+    }
+}
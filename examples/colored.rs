@@ -1,9 +1,9 @@
 /*
-This example requires the `clap` and `stream` features.
+This example requires the `clap` and `colored` features.
 
 Run it with:
 ```bash
-cargo run -q -F=clap --example colored
+cargo run -q -F=clap,colored --example colored
 ```
 
 Try running it:
@@ -13,12 +13,12 @@ Try running it:
 
 For example:
 ```bash
-CLICOLOR_FORCE=1 cargo run -q -F=clap --example colored -- --color=never | cat
+CLICOLOR_FORCE=1 cargo run -q -F=clap,colored --example colored -- --color=never | cat
 ```
 */
 
 use clap::Parser;
-use colored::{control::set_override, Colorize};
+use colored::Colorize;
 use should_color::{clap_color, resolve, ColorChoice};
 
 #[derive(Debug, Parser)]
@@ -41,14 +41,14 @@ fn main() {
     println!("         cli = {cli:?}");
     println!("color_choice = {color_choice:?}");
 
-    set_override(color_stdout);
+    color_choice.apply_to_colored(atty::Stream::Stdout);
     println!(
         "{}: {}",
         "Colorize stdout".bright_green().italic(),
         format!("{}", color_stdout).bright_yellow()
     );
 
-    set_override(color_stderr);
+    color_choice.apply_to_colored(atty::Stream::Stderr);
     eprintln!(
         "{}: {}",
         "Colorize stderr".bright_red().underline(),
@@ -1,9 +1,9 @@
 /**
-This example requires the `clap` and `stream` features.
+This example requires the `clap` and `termcolor` features.
 
 Run it with:
 ```bash
-cargo run -q -F=clap --example termcolor
+cargo run -q -F=clap,termcolor --example termcolor
 ```
 
 Try running it:
@@ -13,7 +13,7 @@ Try running it:
 
 For example:
 ```bash
-CLICOLOR_FORCE=1 cargo run -q -F=clap --example termcolor -- --color=never | cat
+CLICOLOR_FORCE=1 cargo run -q -F=clap,termcolor --example termcolor -- --color=never | cat
 ```
 */
 use clap::Parser;
@@ -41,17 +41,15 @@ fn main() -> std::io::Result<()> {
     println!("         cli = {cli:?}");
     println!("color_choice = {color_choice:?}");
 
-    let mut stdout = StandardStream::stdout(if color_stdout {
-        termcolor::ColorChoice::Always
-    } else {
-        termcolor::ColorChoice::Never
-    });
+    let mut stdout = StandardStream::stdout(should_color::termcolor::color_choice(
+        color_choice,
+        atty::Stream::Stdout,
+    ));
 
-    let mut stderr = StandardStream::stderr(if color_stderr {
-        termcolor::ColorChoice::Always
-    } else {
-        termcolor::ColorChoice::Never
-    });
+    let mut stderr = StandardStream::stderr(should_color::termcolor::color_choice(
+        color_choice,
+        atty::Stream::Stderr,
+    ));
 
     stdout.set_color(
         ColorSpec::new()
@@ -0,0 +1,44 @@
+#ifndef SHOULD_COLOR_H
+#define SHOULD_COLOR_H
+
+/* Generated with cbindgen from src/capi.rs, do not edit manually. */
+
+// No color preference.
+#define SHOULD_COLOR_NONE -1
+
+// The output will not be colorized (see [`ColorChoice::Never`]).
+#define SHOULD_COLOR_NEVER 0
+
+// The output will be colorized if the output device is a tty (see [`ColorChoice::Auto`]).
+#define SHOULD_COLOR_AUTO 1
+
+// The output will be colorized (see [`ColorChoice::Always`]).
+#define SHOULD_COLOR_ALWAYS 2
+
+// The output will be colorized with ANSI escape sequences (see [`ColorChoice::AlwaysAnsi`]).
+#define SHOULD_COLOR_ALWAYS_ANSI 3
+
+// The standard output stream.
+#define SHOULD_COLOR_STDOUT 1
+
+// The standard error stream.
+#define SHOULD_COLOR_STDERR 2
+
+
+
+// Resolve the output color choice from the environment variables and an explicit CLI preference.
+//
+// This is the same as [`resolve`](crate::resolve), where `cli` is one of the `SHOULD_COLOR_*` choices,
+// or `SHOULD_COLOR_NONE` if no preference is expressed.
+// Returns `SHOULD_COLOR_NONE` if no preference is expressed by any source.
+int should_color_resolve(int cli);
+
+// Determine the color setting for a specific stream.
+//
+// This is the same as [`ColorChoice::for_stream`], where `stream` is either
+// `SHOULD_COLOR_STDOUT` or `SHOULD_COLOR_STDERR`.
+// Returns `1` if the output should be colorized and `0` otherwise,
+// or if `choice` or `stream` are invalid.
+int should_color_for_stream(int choice, int stream);
+
+#endif  /* SHOULD_COLOR_H */
@@ -0,0 +1,121 @@
+/*!
+Detection of accessibility settings which call for output without escape sequences.
+
+Screen readers announce the escape sequences as noise, and high-contrast color schemes
+are overridden by the colors of the application.
+This module detects two settings:
+
+- the `ACCESSIBLE` environment variable, a de-facto convention for requesting plain output;
+- the high-contrast mode of Windows (with the <span class="stab portability"><code>windows</code></span> feature).
+
+The source [`SourceId::Accessibility`](crate::SourceId::Accessibility) is not part of the default
+priority order, hence applications opt in by including it in the order of a [`Resolver`](crate::Resolver):
+
+```
+use should_color::{Resolver, SourceId};
+
+let resolver = Resolver::with_order([
+    SourceId::ClicolorForce,
+    SourceId::Cli,
+    SourceId::Accessibility,
+    SourceId::Clicolor,
+    SourceId::NoColor,
+]);
+# let cli_color = None;
+let choice = resolver.resolve(cli_color);
+```
+
+# `ACCESSIBLE`
+
+The meaning of the environment variable is the following:
+
+- if not set or `ACCESSIBLE == ""` or `ACCESSIBLE == "0"`: ignore;
+- otherwise: [`ColorChoice::Never`].
+*/
+
+use crate::ColorChoice;
+
+/// Name of the `ACCESSIBLE` environment variable.
+pub const ACCESSIBLE: &str = "ACCESSIBLE";
+
+/// Determine whether the `ACCESSIBLE` environment variable requests plain output.
+pub fn accessible_env() -> bool {
+    accessible_with(crate::env_var)
+}
+
+/// Same as [`accessible_env`], but for the given environment variables.
+fn accessible_with(var: impl Fn(&str) -> Option<String>) -> bool {
+    var(ACCESSIBLE).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/**
+Determine whether the high-contrast mode of Windows is enabled.
+
+Returns `false` if the setting cannot be read, or on platforms other than Windows.
+*/
+#[cfg(feature = "windows")]
+pub fn high_contrast() -> bool {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            SystemParametersInfoW, SPI_GETHIGHCONTRAST,
+        };
+
+        let mut info = HIGHCONTRASTW {
+            cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+            ..Default::default()
+        };
+        // SAFETY: `info` is a valid `HIGHCONTRASTW` whose size is given in `cbSize`.
+        let status = unsafe {
+            SystemParametersInfoW(
+                SPI_GETHIGHCONTRAST,
+                info.cbSize,
+                (&mut info as *mut HIGHCONTRASTW).cast(),
+                0,
+            )
+        };
+        status != 0 && info.dwFlags & HCF_HIGHCONTRASTON != 0
+    }
+    #[cfg(not(windows))]
+    false
+}
+
+/**
+Get the color choice expressed by the accessibility settings.
+
+Returns `Some(`[`ColorChoice::Never`]`)` if `ACCESSIBLE` requests plain output (see [`accessible_env`])
+or the high-contrast mode of Windows is enabled (see [`high_contrast`], which requires the
+<span class="stab portability"><code>windows</code></span> feature), and `None` otherwise.
+*/
+pub fn accessibility() -> Option<ColorChoice> {
+    accessibility_with(crate::env_var)
+}
+
+/// Same as [`accessibility`], but `ACCESSIBLE` is read from the given environment variables.
+pub(crate) fn accessibility_with(var: impl Fn(&str) -> Option<String>) -> Option<ColorChoice> {
+    #[cfg(feature = "windows")]
+    let high_contrast = high_contrast();
+    #[cfg(not(feature = "windows"))]
+    let high_contrast = false;
+    (accessible_with(var) || high_contrast).then_some(ColorChoice::Never)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accessible() {
+        std::env::remove_var(ACCESSIBLE);
+        assert!(!accessible_env());
+
+        std::env::set_var(ACCESSIBLE, "0");
+        assert!(!accessible_env());
+
+        std::env::set_var(ACCESSIBLE, "1");
+        assert!(accessible_env());
+        assert_eq!(accessibility(), Some(ColorChoice::Never));
+        std::env::remove_var(ACCESSIBLE);
+    }
+}
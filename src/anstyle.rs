@@ -0,0 +1,129 @@
+/*!
+Interoperability with [`anstyle`](https://docs.rs/anstyle/latest/anstyle/).
+
+A [`Style`](https://docs.rs/anstyle/latest/anstyle/struct.Style.html) can be applied to some text
+only when coloring is enabled, by means of [`ColorChoice::paint`] or [`Styled::new`].
+
+Moreover [`ColorChoice`] can be converted to and from
+[`colorchoice::ColorChoice`](https://docs.rs/colorchoice/latest/colorchoice/enum.ColorChoice.html),
+which is the choice type used by [`anstream`](https://docs.rs/anstream/latest/anstream/).
+
+```
+use anstyle::{AnsiColor, Style};
+use should_color::anstyle::Styled;
+
+let warning = Style::new().bold().fg_color(Some(AnsiColor::Yellow.into()));
+
+assert_eq!(Styled::new(false, warning, "warning").to_string(), "warning");
+assert_eq!(
+    Styled::new(true, warning, "warning").to_string(),
+    "\x1b[1m\x1b[33mwarning\x1b[0m"
+);
+```
+*/
+
+use std::fmt;
+
+use ::anstyle::Style;
+
+use crate::ColorChoice;
+
+/**
+Some text which is displayed with a [`Style`](https://docs.rs/anstyle/latest/anstyle/struct.Style.html)
+only if coloring is enabled.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Styled<T> {
+    enabled: bool,
+    style: Style,
+    text: T,
+}
+
+impl<T> Styled<T> {
+    /// Wrap `text` so that it is displayed with `style` if `enabled` is `true`, and plainly otherwise.
+    pub fn new(enabled: bool, style: Style, text: T) -> Styled<T> {
+        Styled {
+            enabled,
+            style,
+            text,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Styled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.enabled {
+            write!(
+                f,
+                "{}{}{}",
+                self.style.render(),
+                self.text,
+                self.style.render_reset()
+            )
+        } else {
+            self.text.fmt(f)
+        }
+    }
+}
+
+impl ColorChoice {
+    /**
+    Display `text` with `style`, but only if coloring is enabled for the standard output.
+
+    This is equivalent to
+    [`Styled::new`]`(self.`[`for_stream`](ColorChoice::for_stream)`(atty::Stream::Stdout), style, text)`.
+    */
+    pub fn paint<T: fmt::Display>(&self, style: Style, text: T) -> Styled<T> {
+        Styled::new(self.for_stream(atty::Stream::Stdout), style, text)
+    }
+}
+
+impl From<ColorChoice> for colorchoice::ColorChoice {
+    fn from(color_choice: ColorChoice) -> colorchoice::ColorChoice {
+        match color_choice {
+            ColorChoice::Never => colorchoice::ColorChoice::Never,
+            ColorChoice::Auto => colorchoice::ColorChoice::Auto,
+            ColorChoice::Always => colorchoice::ColorChoice::Always,
+            ColorChoice::AlwaysAnsi => colorchoice::ColorChoice::AlwaysAnsi,
+        }
+    }
+}
+
+impl From<colorchoice::ColorChoice> for ColorChoice {
+    fn from(color_choice: colorchoice::ColorChoice) -> ColorChoice {
+        match color_choice {
+            colorchoice::ColorChoice::Never => ColorChoice::Never,
+            colorchoice::ColorChoice::Auto => ColorChoice::Auto,
+            colorchoice::ColorChoice::Always => ColorChoice::Always,
+            colorchoice::ColorChoice::AlwaysAnsi => ColorChoice::AlwaysAnsi,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint() {
+        let style = Style::new().italic();
+        assert_eq!(ColorChoice::Never.paint(style, 42).to_string(), "42");
+        assert_eq!(
+            ColorChoice::Always.paint(style, 42).to_string(),
+            "\x1b[3m42\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        for choice in [
+            ColorChoice::Never,
+            ColorChoice::Auto,
+            ColorChoice::Always,
+            ColorChoice::AlwaysAnsi,
+        ] {
+            let converted: colorchoice::ColorChoice = choice.into();
+            assert_eq!(ColorChoice::from(converted), choice);
+        }
+    }
+}
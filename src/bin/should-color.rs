@@ -0,0 +1,114 @@
+/*!
+Determine from a shell script whether output should use colors or not.
+
+The resolution is the same as [`should_color::resolve_streams`], followed by
+[`ColorChoice::for_stream`] on the selected stream.
+The result is communicated via the exit status (`0` for colors, `1` for no colors),
+or printed as `never`, `auto`, or `always` if `--print` is given.
+
+```sh
+if should-color --stream stderr; then
+    printf '\033[31merror\033[0m\n' >&2
+else
+    printf 'error\n' >&2
+fi
+```
+
+With `--export` the decision is printed as shell commands which set `CLICOLOR_FORCE=1`
+or `NO_COLOR=1` (and unset the other), so that a wrapper script can propagate it to the tools it invokes.
+The syntax is selected with `--shell` (`bash`, `fish`, or `powershell`).
+Notice that the standard output is captured by the command substitution,
+hence the decision should be taken for the standard error:
+
+```sh
+eval "$(should-color --export --stream stderr)"
+```
+*/
+
+use clap::{Parser, ValueEnum};
+
+use should_color::{resolve_streams, ColorChoice};
+
+/// Determine whether output should use colors or not.
+#[derive(Debug, Parser)]
+#[clap(version, color = should_color::clap_color())]
+struct Cli {
+    /// Explicit color preference, for instance forwarded from the arguments of the script
+    #[clap(long, value_enum, value_name = "WHEN")]
+    color: Option<ColorChoice>,
+
+    /// Color choice used when no preference is expressed
+    #[clap(long, value_enum, value_name = "WHEN", default_value = "auto")]
+    default: ColorChoice,
+
+    /// Stream where the output is written
+    #[clap(long, value_enum, default_value = "stdout")]
+    stream: Stream,
+
+    /// Print the resolved choice instead of reporting it via the exit status
+    #[clap(long, conflicts_with = "export")]
+    print: bool,
+
+    /// Print shell commands which export the decision to child processes
+    #[clap(long)]
+    export: bool,
+
+    /// Syntax of the commands printed by --export
+    #[clap(long, value_enum, default_value = "bash", requires = "export")]
+    shell: Shell,
+}
+
+/// Shells whose syntax is supported by `--export`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Shell {
+    /// POSIX shells, such as bash and zsh
+    #[clap(alias = "sh", alias = "zsh")]
+    Bash,
+    Fish,
+    #[clap(alias = "pwsh")]
+    Powershell,
+}
+
+impl Shell {
+    /// Print the commands which set the variable `set` to `1` and unset the variable `unset`.
+    fn export(self, set: &str, unset: &str) {
+        match self {
+            Shell::Bash => println!("export {set}=1\nunset {unset}"),
+            Shell::Fish => println!("set -gx {set} 1\nset -e {unset}"),
+            Shell::Powershell => println!(
+                "$env:{set} = \"1\"\nRemove-Item Env:{unset} -ErrorAction SilentlyContinue"
+            ),
+        }
+    }
+}
+
+/// Standard output streams.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let choices = resolve_streams(cli.color);
+    let (choice, stream) = match cli.stream {
+        Stream::Stdout => (choices.stdout, atty::Stream::Stdout),
+        Stream::Stderr => (choices.stderr, atty::Stream::Stderr),
+    };
+    let choice = choice.unwrap_or(cli.default);
+
+    if cli.export {
+        if choice.for_stream(stream) {
+            cli.shell.export("CLICOLOR_FORCE", "NO_COLOR");
+        } else {
+            cli.shell.export("NO_COLOR", "CLICOLOR_FORCE");
+        }
+    } else if cli.print {
+        let value = choice.to_possible_value().expect("no skipped variants");
+        println!("{}", value.get_name());
+    } else if !choice.for_stream(stream) {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,117 @@
+/*!
+Analysis of the output capabilities in a single probe.
+
+Frameworks which set up several output subsystems, such as a logger, progress bars, and diagnostics,
+would otherwise call [`resolve`](crate::resolve), [`depth::resolve_depth`](crate::depth::resolve_depth),
+[`hyperlinks::resolve_hyperlinks`](crate::hyperlinks::resolve_hyperlinks),
+[`unicode::resolve_unicode`](crate::unicode::resolve_unicode), and [`terminal::detect`](crate::terminal::detect)
+separately, each one reading the environment again.
+[`Capabilities::detect`] performs the whole analysis once and returns a single value,
+which can be shared by all the subsystems and, with the
+<span class="stab portability"><code>serde</code></span> feature, serialized for diagnostics.
+
+```
+use should_color::capabilities::Capabilities;
+use should_color::ColorChoice;
+
+# std::env::remove_var("CLICOLOR_FORCE");
+let capabilities = Capabilities::detect(Some(ColorChoice::Never));
+assert_eq!(capabilities.choice, ColorChoice::Never);
+assert!(!capabilities.stdout.color && !capabilities.stderr.color);
+if capabilities.stderr.unicode {
+    eprintln!("✔ done");
+}
+```
+*/
+
+use crate::depth::{resolve_depth, ColorDepth};
+use crate::hyperlinks::{resolve_hyperlinks, HyperlinkChoice};
+use crate::terminal::TerminalKind;
+use crate::unicode::{resolve_unicode, UnicodeChoice};
+use crate::{ColorChoice, ResolvedStreams};
+
+/// The capabilities of a single output stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StreamCapabilities {
+    /// Whether the stream should be colorized, according to [`ColorChoice::for_stream`].
+    pub color: bool,
+    /// Whether the stream should contain hyperlinks, according to [`HyperlinkChoice::for_stream`].
+    pub hyperlinks: bool,
+    /// Whether the stream should use Unicode glyphs, according to [`UnicodeChoice::for_stream`].
+    pub unicode: bool,
+}
+
+impl StreamCapabilities {
+    /// Determine the capabilities of `stream` for the given choices.
+    fn detect(
+        stream: atty::Stream,
+        choice: ColorChoice,
+        hyperlinks: HyperlinkChoice,
+        unicode: UnicodeChoice,
+    ) -> StreamCapabilities {
+        StreamCapabilities {
+            color: choice.for_stream(stream),
+            hyperlinks: hyperlinks.for_stream(stream),
+            unicode: unicode.for_stream(stream),
+        }
+    }
+}
+
+/**
+The output capabilities of the program, as determined by a single analysis.
+
+See the [module documentation](self) for an example.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities {
+    /// The resolved color choice, with a default of [`ColorChoice::Auto`].
+    pub choice: ColorChoice,
+    /**
+    The color depth, as determined by [`resolve_depth`].
+
+    It is [`ColorDepth::NoColor`] if the choice is [`ColorChoice::Never`],
+    and should be taken into account only for the streams which are colorized.
+    */
+    pub depth: ColorDepth,
+    /// The terminal emulator, as identified by [`terminal::detect`](crate::terminal::detect).
+    pub terminal: TerminalKind,
+    /// The capabilities of the standard output.
+    pub stdout: StreamCapabilities,
+    /// The capabilities of the standard error.
+    pub stderr: StreamCapabilities,
+}
+
+impl Capabilities {
+    /**
+    Analyze the environment and the standard streams, with an explicit CLI color preference.
+
+    The color choice and the color depth are computed by [`resolve_depth`]`(cli, None)`,
+    while the hyperlinks and the Unicode glyphs follow their environment variables,
+    with a default of `Auto`.
+    */
+    pub fn detect(cli: Option<ColorChoice>) -> Capabilities {
+        let (choice, depth) = resolve_depth(cli, None);
+        let choice = choice.unwrap_or(ColorChoice::Auto);
+        let hyperlinks = resolve_hyperlinks(None).unwrap_or(HyperlinkChoice::Auto);
+        let unicode = resolve_unicode(None).unwrap_or(UnicodeChoice::Auto);
+        let stream = |stream| StreamCapabilities::detect(stream, choice, hyperlinks, unicode);
+        Capabilities {
+            choice,
+            depth,
+            terminal: crate::terminal::detect(),
+            stdout: stream(atty::Stream::Stdout),
+            stderr: stream(atty::Stream::Stderr),
+        }
+    }
+
+    /// Get the color settings of the standard streams, as returned by [`init`](crate::init).
+    pub fn streams(&self) -> ResolvedStreams {
+        ResolvedStreams {
+            choice: self.choice,
+            stdout: self.stdout.color,
+            stderr: self.stderr.color,
+        }
+    }
+}
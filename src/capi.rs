@@ -0,0 +1,119 @@
+/*!
+C API for the resolution of the color choice.
+
+The functions of this module are exported with C linkage, so that C and C++ programs
+can share the same semantics of `NO_COLOR`, `CLICOLOR`, and `CLICOLOR_FORCE` as Rust consumers.
+The color choices are represented as integers by the constants of this module,
+and the header `include/should_color.h` is generated with [cbindgen](https://github.com/mozilla/cbindgen).
+
+To build a shared or static library, run
+
+```sh
+cargo rustc --release --features capi --crate-type cdylib
+cargo rustc --release --features capi --crate-type staticlib
+```
+*/
+
+use std::os::raw::c_int;
+
+use crate::ColorChoice;
+
+/// No color preference.
+pub const SHOULD_COLOR_NONE: c_int = -1;
+/// The output will not be colorized (see [`ColorChoice::Never`]).
+pub const SHOULD_COLOR_NEVER: c_int = 0;
+/// The output will be colorized if the output device is a tty (see [`ColorChoice::Auto`]).
+pub const SHOULD_COLOR_AUTO: c_int = 1;
+/// The output will be colorized (see [`ColorChoice::Always`]).
+pub const SHOULD_COLOR_ALWAYS: c_int = 2;
+/// The output will be colorized with ANSI escape sequences (see [`ColorChoice::AlwaysAnsi`]).
+pub const SHOULD_COLOR_ALWAYS_ANSI: c_int = 3;
+
+/// The standard output stream.
+pub const SHOULD_COLOR_STDOUT: c_int = 1;
+/// The standard error stream.
+pub const SHOULD_COLOR_STDERR: c_int = 2;
+
+/// Convert an integer to an optional color choice, mapping invalid values to `None`.
+fn from_c(choice: c_int) -> Option<ColorChoice> {
+    match choice {
+        SHOULD_COLOR_NEVER => Some(ColorChoice::Never),
+        SHOULD_COLOR_AUTO => Some(ColorChoice::Auto),
+        SHOULD_COLOR_ALWAYS => Some(ColorChoice::Always),
+        SHOULD_COLOR_ALWAYS_ANSI => Some(ColorChoice::AlwaysAnsi),
+        _ => None,
+    }
+}
+
+/// Convert an optional color choice to an integer.
+fn to_c(choice: Option<ColorChoice>) -> c_int {
+    match choice {
+        None => SHOULD_COLOR_NONE,
+        Some(ColorChoice::Never) => SHOULD_COLOR_NEVER,
+        Some(ColorChoice::Auto) => SHOULD_COLOR_AUTO,
+        Some(ColorChoice::Always) => SHOULD_COLOR_ALWAYS,
+        Some(ColorChoice::AlwaysAnsi) => SHOULD_COLOR_ALWAYS_ANSI,
+    }
+}
+
+/**
+Resolve the output color choice from the environment variables and an explicit CLI preference.
+
+This is the same as [`resolve`](crate::resolve), where `cli` is one of the `SHOULD_COLOR_*` choices,
+or `SHOULD_COLOR_NONE` if no preference is expressed.
+Returns `SHOULD_COLOR_NONE` if no preference is expressed by any source.
+*/
+#[no_mangle]
+pub extern "C" fn should_color_resolve(cli: c_int) -> c_int {
+    to_c(crate::resolve(from_c(cli)))
+}
+
+/**
+Determine the color setting for a specific stream.
+
+This is the same as [`ColorChoice::for_stream`], where `stream` is either
+`SHOULD_COLOR_STDOUT` or `SHOULD_COLOR_STDERR`.
+Returns `1` if the output should be colorized and `0` otherwise,
+or if `choice` or `stream` are invalid.
+*/
+#[no_mangle]
+pub extern "C" fn should_color_for_stream(choice: c_int, stream: c_int) -> c_int {
+    let stream = match stream {
+        SHOULD_COLOR_STDOUT => atty::Stream::Stdout,
+        SHOULD_COLOR_STDERR => atty::Stream::Stderr,
+        _ => return 0,
+    };
+    from_c(choice).is_some_and(|choice| choice.for_stream(stream)) as c_int
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        for choice in [
+            None,
+            Some(ColorChoice::Never),
+            Some(ColorChoice::Auto),
+            Some(ColorChoice::Always),
+            Some(ColorChoice::AlwaysAnsi),
+        ] {
+            assert_eq!(from_c(to_c(choice)), choice);
+        }
+        assert_eq!(from_c(42), None);
+    }
+
+    #[test]
+    fn test_for_stream() {
+        assert_eq!(
+            should_color_for_stream(SHOULD_COLOR_ALWAYS, SHOULD_COLOR_STDOUT),
+            1
+        );
+        assert_eq!(
+            should_color_for_stream(SHOULD_COLOR_NEVER, SHOULD_COLOR_STDERR),
+            0
+        );
+        assert_eq!(should_color_for_stream(SHOULD_COLOR_ALWAYS, 42), 0);
+    }
+}
@@ -0,0 +1,146 @@
+/*!
+Detection of continuous integration services.
+
+The logs of a CI job are not TTYs, but many services render ANSI colors in their web interface.
+The service is identified by [`provider`] from the environment variables it sets,
+and [`CiProvider::supports_ansi`] tells whether its logs render colors.
+[`Resolver::promote_in_ci`](crate::Resolver::promote_in_ci) takes this into account
+to promote [`ColorChoice::Auto`](crate::ColorChoice::Auto) only where colors are actually rendered.
+
+```
+use should_color::ci::CiProvider;
+
+assert!(CiProvider::GitHubActions.supports_ansi());
+assert!(!CiProvider::Jenkins { ansi_color: false }.supports_ansi());
+```
+*/
+
+/// Known continuous integration services.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CiProvider {
+    /// [AppVeyor](https://www.appveyor.com), detected from `APPVEYOR`.
+    AppVeyor,
+    /// [Azure Pipelines](https://azure.microsoft.com/products/devops/pipelines), detected from `TF_BUILD`.
+    AzurePipelines,
+    /// [Bitbucket Pipelines](https://bitbucket.org/product/features/pipelines),
+    /// detected from `BITBUCKET_BUILD_NUMBER`.
+    BitbucketPipelines,
+    /// [Buildkite](https://buildkite.com), detected from `BUILDKITE`.
+    Buildkite,
+    /// [CircleCI](https://circleci.com), detected from `CIRCLECI`.
+    CircleCi,
+    /// [Drone](https://www.drone.io), detected from `DRONE`.
+    Drone,
+    /// [GitHub Actions](https://github.com/features/actions), detected from `GITHUB_ACTIONS`.
+    GitHubActions,
+    /// [GitLab CI/CD](https://docs.gitlab.com/ee/ci/), detected from `GITLAB_CI`.
+    GitLab,
+    /**
+    [Jenkins](https://www.jenkins.io), detected from `JENKINS_URL`.
+
+    The console of Jenkins renders colors only with the
+    [AnsiColor plugin](https://plugins.jenkins.io/ansicolor/), which is detected from the `TERM`
+    environment variable that it exports.
+    */
+    Jenkins {
+        /// Whether the AnsiColor plugin is active.
+        ansi_color: bool,
+    },
+    /// [TeamCity](https://www.jetbrains.com/teamcity/), detected from `TEAMCITY_VERSION`.
+    TeamCity,
+    /// [Travis CI](https://www.travis-ci.com), detected from `TRAVIS`.
+    Travis,
+    /// [Woodpecker](https://woodpecker-ci.org), detected from `CI=woodpecker`.
+    Woodpecker,
+    /// A service which sets the generic `CI` environment variable, but could not be identified.
+    Unknown,
+}
+
+impl CiProvider {
+    /// Whether the logs of the service render ANSI colors.
+    pub fn supports_ansi(&self) -> bool {
+        match self {
+            CiProvider::Jenkins { ansi_color } => *ansi_color,
+            CiProvider::Unknown => false,
+            _ => true,
+        }
+    }
+}
+
+/// Identify the continuous integration service from the given environment variables.
+pub(crate) fn provider_with(var: impl Fn(&str) -> Option<String>) -> Option<CiProvider> {
+    let is_set = |name| var(name).is_some_and(|value| !value.is_empty());
+
+    const TABLE: [(&str, CiProvider); 11] = [
+        ("GITHUB_ACTIONS", CiProvider::GitHubActions),
+        ("GITLAB_CI", CiProvider::GitLab),
+        ("BUILDKITE", CiProvider::Buildkite),
+        ("CIRCLECI", CiProvider::CircleCi),
+        ("TRAVIS", CiProvider::Travis),
+        ("TF_BUILD", CiProvider::AzurePipelines),
+        ("APPVEYOR", CiProvider::AppVeyor),
+        ("BITBUCKET_BUILD_NUMBER", CiProvider::BitbucketPipelines),
+        ("TEAMCITY_VERSION", CiProvider::TeamCity),
+        ("DRONE", CiProvider::Drone),
+        ("JENKINS_URL", CiProvider::Jenkins { ansi_color: false }),
+    ];
+
+    match TABLE.iter().find(|(name, _)| is_set(name)) {
+        Some((_, CiProvider::Jenkins { .. })) => Some(CiProvider::Jenkins {
+            ansi_color: var("TERM").is_some_and(|term| !term.is_empty() && term != "dumb"),
+        }),
+        Some((_, provider)) => Some(*provider),
+        None => match var("CI").as_deref() {
+            Some("woodpecker") => Some(CiProvider::Woodpecker),
+            Some(value) if !value.is_empty() && value != "false" && value != "0" => {
+                Some(CiProvider::Unknown)
+            }
+            _ => None,
+        },
+    }
+}
+
+/// Detect whether the program is running inside a continuous integration service.
+pub fn provider() -> Option<CiProvider> {
+    provider_with(crate::env_var)
+}
+
+/// Detect whether the program is running inside a continuous integration service whose logs render ANSI colors.
+pub fn supports_ansi() -> bool {
+    provider().is_some_and(|provider| provider.supports_ansi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EnvSnapshot;
+
+    fn provider_from(vars: &[(&str, &str)]) -> Option<CiProvider> {
+        let env = EnvSnapshot::from_vars(vars.iter().copied());
+        provider_with(|name| env.var(name))
+    }
+
+    #[test]
+    fn test_provider() {
+        assert_eq!(provider_from(&[]), None);
+        assert_eq!(provider_from(&[("CI", "false")]), None);
+        assert_eq!(
+            provider_from(&[("CI", "true"), ("GITHUB_ACTIONS", "true")]),
+            Some(CiProvider::GitHubActions)
+        );
+        assert_eq!(
+            provider_from(&[("CI", "woodpecker")]),
+            Some(CiProvider::Woodpecker)
+        );
+        assert_eq!(provider_from(&[("CI", "1")]), Some(CiProvider::Unknown));
+        assert_eq!(
+            provider_from(&[("JENKINS_URL", "http://ci/")]),
+            Some(CiProvider::Jenkins { ansi_color: false })
+        );
+        assert_eq!(
+            provider_from(&[("JENKINS_URL", "http://ci/"), ("TERM", "xterm")]),
+            Some(CiProvider::Jenkins { ansi_color: true })
+        );
+    }
+}
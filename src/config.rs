@@ -0,0 +1,174 @@
+/*!
+Color preference persisted in a user configuration file.
+
+A [`FileSource`] reads the preference either from a plain file containing one of
+`never`, `auto`, `always`, or from a key of a [TOML](https://toml.io) file.
+It is meant to be appended to a [`Resolver`](crate::Resolver), below the environment variables,
+so that the user can persist a preference which can still be overridden.
+
+```no_run
+use should_color::config::FileSource;
+use should_color::Resolver;
+
+// reads `$XDG_CONFIG_HOME/my-app/color`
+let resolver = Resolver::new().source(FileSource::for_app("my-app"));
+# let cli_color = None;
+let color_choice = resolver.resolve(cli_color);
+```
+*/
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::resolver::Source;
+use crate::ColorChoice;
+
+/// Format of the configuration file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Format {
+    /// The whole file contains the preference.
+    Plain,
+    /// The preference is the value of a (possibly dotted) key of a TOML file.
+    Toml(String),
+}
+
+/**
+Source which reads a color preference from a configuration file.
+
+The file is read at most once, the first time the preference is requested,
+and the result is cached for the lifetime of the source.
+If the file does not exist or does not contain a valid preference,
+the source expresses no preference.
+*/
+#[derive(Clone, Debug)]
+pub struct FileSource {
+    path: PathBuf,
+    format: Format,
+    cache: OnceLock<Option<ColorChoice>>,
+}
+
+impl FileSource {
+    /// Create a source which reads the whole file at `path`, ignoring surrounding whitespace.
+    pub fn new(path: impl Into<PathBuf>) -> FileSource {
+        FileSource {
+            path: path.into(),
+            format: Format::Plain,
+            cache: OnceLock::new(),
+        }
+    }
+
+    /**
+    Create a source which reads the value of `key` in the TOML file at `path`.
+
+    The `key` can be dotted to refer to nested tables, as in `"ui.color"`.
+    */
+    pub fn toml(path: impl Into<PathBuf>, key: impl Into<String>) -> FileSource {
+        FileSource {
+            path: path.into(),
+            format: Format::Toml(key.into()),
+            cache: OnceLock::new(),
+        }
+    }
+
+    /**
+    Create a source which reads the plain file `color` in the configuration directory of `app`.
+
+    The configuration directory is `$XDG_CONFIG_HOME/<app>`, falling back to `$HOME/.config/<app>`,
+    and `%APPDATA%\<app>` on Windows.
+    */
+    pub fn for_app(app: &str) -> FileSource {
+        FileSource::new(config_dir().unwrap_or_default().join(app).join("color"))
+    }
+
+    /// Path of the configuration file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the preference from the file, bypassing the cache.
+    fn read(&self) -> Option<ColorChoice> {
+        let contents = std::fs::read_to_string(&self.path).ok()?;
+        match &self.format {
+            Format::Plain => parse_plain(&contents),
+            Format::Toml(key) => parse_toml(&contents, key),
+        }
+    }
+}
+
+impl Source for FileSource {
+    fn get(&self) -> Option<ColorChoice> {
+        *self.cache.get_or_init(|| self.read())
+    }
+}
+
+/// Base directory for user configuration files.
+fn config_dir() -> Option<PathBuf> {
+    let var = |name| crate::env_var_os(name).filter(|value| !value.is_empty());
+    if cfg!(windows) {
+        var("APPDATA").map(PathBuf::from)
+    } else {
+        var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }
+}
+
+fn parse_plain(contents: &str) -> Option<ColorChoice> {
+    match contents.trim() {
+        "" => None,
+        value => value.parse().ok(),
+    }
+}
+
+fn parse_toml(contents: &str, key: &str) -> Option<ColorChoice> {
+    let table: toml::Table = contents.parse().ok()?;
+    let mut parts = key.split('.');
+    let mut value = table.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_table()?.get(part)?;
+    }
+    match value.as_str()? {
+        "" => None,
+        value => value.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain() {
+        assert_eq!(parse_plain("never\n"), Some(ColorChoice::Never));
+        assert_eq!(parse_plain("  always "), Some(ColorChoice::Always));
+        assert_eq!(parse_plain("\n"), None);
+        assert_eq!(parse_plain("blue"), None);
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        let contents = "color = \"auto\"\n[ui]\ncolor = \"never\"\nwidth = 80\n";
+        assert_eq!(parse_toml(contents, "color"), Some(ColorChoice::Auto));
+        assert_eq!(parse_toml(contents, "ui.color"), Some(ColorChoice::Never));
+        assert_eq!(parse_toml(contents, "ui.width"), None);
+        assert_eq!(parse_toml(contents, "ui.missing"), None);
+        assert_eq!(parse_toml(contents, "color.nested"), None);
+        assert_eq!(parse_toml("not toml [", "color"), None);
+    }
+
+    #[test]
+    fn test_file_source() {
+        let path = std::env::temp_dir().join(format!("should-color-test-{}", std::process::id()));
+        std::fs::write(&path, "never\n").unwrap();
+        let source = FileSource::new(&path);
+        assert_eq!(source.get(), Some(ColorChoice::Never));
+
+        // the result is cached
+        std::fs::write(&path, "always\n").unwrap();
+        assert_eq!(source.get(), Some(ColorChoice::Never));
+        assert_eq!(FileSource::new(&path).get(), Some(ColorChoice::Always));
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(FileSource::new(&path).get(), None);
+    }
+}
@@ -0,0 +1,645 @@
+/*!
+Detection of the color depth supported by the terminal.
+
+The color depth is determined by taking into account,
+in order of priority from higher to lower, the following settings:
+
+- `COLORTERM` environment variable, which signals support for 24-bit colors
+  if set to `truecolor` or `24bit`;
+- the terminal emulator, as identified by [`terminal::detect`](crate::terminal::detect),
+  if it is known to support 24-bit colors;
+- the terminfo database (requires the <span class="stab portability"><code>terminfo</code></span> feature);
+- the bundled table of common terminals (see the [`termdb`](crate::termdb) module);
+- `TERM` environment variable, by matching the name of the terminal against common patterns.
+
+The resolution is adjusted inside terminal multiplexers and SSH sessions,
+and can be configured with [`DepthResolver`].
+
+Notice that the color depth describes what the terminal is capable of,
+hence it should be taken into account only after determining that the output should be colorized.
+
+Applications can define a palette of 24-bit colors and adapt it to the detected depth
+with [`ColorDepth::adapt_rgb`], which maps each color to the nearest one that the terminal supports.
+
+```
+use should_color::depth::ColorDepth;
+use should_color::styler::Color;
+
+let orange = (255, 135, 0);
+assert_eq!(ColorDepth::TrueColor.adapt_rgb(orange), Some(Color::Rgb(255, 135, 0)));
+assert_eq!(ColorDepth::Ansi256.adapt_rgb(orange), Some(Color::Fixed(208)));
+assert_eq!(ColorDepth::Ansi16.adapt_rgb(orange), Some(Color::Ansi(3)));
+assert_eq!(ColorDepth::NoColor.adapt_rgb(orange), None);
+```
+*/
+
+use crate::styler::Color;
+use crate::terminal::Multiplexer;
+use crate::{ColorChoice, Resolver, SourceId};
+
+/// Name of the `COLORTERM` environment variable.
+pub const COLORTERM: &str = "COLORTERM";
+/// Name of the `TERM` environment variable.
+pub const TERM: &str = "TERM";
+/// Name of the `FORCE_COLOR` environment variable.
+pub const FORCE_COLOR: &str = "FORCE_COLOR";
+
+/// Number of colors supported by the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum ColorDepth {
+    /// No colors are supported.
+    NoColor,
+    /// The 8 basic colors and their bright variants are supported.
+    Ansi16,
+    /// The 256-color palette is supported.
+    Ansi256,
+    /// 24-bit RGB colors are supported.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Determine the color depth from the number of colors supported by the terminal.
+    pub fn from_max_colors(max_colors: u32) -> ColorDepth {
+        match max_colors {
+            0..=7 => ColorDepth::NoColor,
+            8..=255 => ColorDepth::Ansi16,
+            256..=0xFF_FFFF => ColorDepth::Ansi256,
+            _ => ColorDepth::TrueColor,
+        }
+    }
+}
+
+impl ColorDepth {
+    /**
+    Adapt a 24-bit color to the color depth, mapping it to the nearest supported color.
+
+    See [`rgb_to_ansi256`] and [`rgb_to_ansi16`] for the mappings.
+    Returns `None` for [`ColorDepth::NoColor`].
+    */
+    pub fn adapt_rgb(self, (r, g, b): (u8, u8, u8)) -> Option<Color> {
+        match self {
+            ColorDepth::NoColor => None,
+            ColorDepth::Ansi16 => Some(Color::Ansi(rgb_to_ansi16(r, g, b))),
+            ColorDepth::Ansi256 => Some(Color::Fixed(rgb_to_ansi256(r, g, b))),
+            ColorDepth::TrueColor => Some(Color::Rgb(r, g, b)),
+        }
+    }
+
+    /**
+    Adapt a color to the color depth, mapping it to the nearest supported color
+    if it exceeds the depth.
+
+    Returns `None` for [`ColorDepth::NoColor`].
+    */
+    pub fn adapt(self, color: Color) -> Option<Color> {
+        match (self, color) {
+            (ColorDepth::NoColor, _) => None,
+            (_, Color::Rgb(r, g, b)) => self.adapt_rgb((r, g, b)),
+            (ColorDepth::Ansi16, Color::Fixed(n)) => {
+                Some(Color::Ansi(if n < 16 { n } else { ansi256_to_ansi16(n) }))
+            }
+            _ => Some(color),
+        }
+    }
+}
+
+/// The levels of the components of the 6×6×6 color cube of the 256-color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/**
+The 16 ANSI colors, as rendered by the default palette of xterm.
+
+The actual colors depend on the terminal and its theme, but this palette is a common reference.
+*/
+const ANSI16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Squared Euclidean distance between two colors.
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).unsigned_abs().pow(2);
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/**
+Get the 24-bit color of an index of the 256-color palette, as rendered by xterm.
+
+The indices `0..16` are the ANSI colors, `16..232` the 6×6×6 color cube, and `232..256` the grayscale ramp.
+*/
+pub fn ansi256_to_rgb(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..=15 => ANSI16_PALETTE[usize::from(n)],
+        16..=231 => {
+            let n = usize::from(n - 16);
+            (
+                CUBE_LEVELS[n / 36],
+                CUBE_LEVELS[n / 6 % 6],
+                CUBE_LEVELS[n % 6],
+            )
+        }
+        232..=255 => {
+            let level = 8 + 10 * (n - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/**
+Map a 24-bit color to the nearest index of the 256-color palette,
+choosing between the color cube and the grayscale ramp (the ANSI colors are not considered,
+since they depend on the theme of the terminal).
+*/
+pub fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let nearest_level = |c: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| CUBE_LEVELS[i].abs_diff(c))
+            .unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * nearest_level(r) + 6 * nearest_level(g) + nearest_level(b);
+    let average = ((u16::from(r) + u16::from(g) + u16::from(b)) / 3) as u8;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23);
+    [cube, gray]
+        .into_iter()
+        .min_by_key(|&n| distance((r, g, b), ansi256_to_rgb(n)))
+        .unwrap_or(cube)
+}
+
+/// Map a 24-bit color to the nearest of the 16 ANSI colors (`0..8` basic, `8..16` bright).
+pub fn rgb_to_ansi16(r: u8, g: u8, b: u8) -> u8 {
+    (0..16)
+        .min_by_key(|&n| distance((r, g, b), ANSI16_PALETTE[usize::from(n)]))
+        .unwrap_or(0)
+}
+
+/// Map an index of the 256-color palette to the nearest of the 16 ANSI colors.
+pub fn ansi256_to_ansi16(n: u8) -> u8 {
+    let (r, g, b) = ansi256_to_rgb(n);
+    rgb_to_ansi16(r, g, b)
+}
+
+/**
+Get the color depth signaled by the `COLORTERM` environment variable.
+
+Returns `Some(`[`ColorDepth::TrueColor`]`)` if `COLORTERM` is `truecolor` or `24bit`, and `None` otherwise.
+*/
+pub fn colorterm() -> Option<ColorDepth> {
+    depth_from_colorterm(crate::env_var(COLORTERM).as_deref()?)
+}
+
+/// Interpret the value of the `COLORTERM` environment variable.
+fn depth_from_colorterm(colorterm: &str) -> Option<ColorDepth> {
+    match colorterm {
+        "truecolor" | "24bit" => Some(ColorDepth::TrueColor),
+        _ => None,
+    }
+}
+
+/**
+Get the setting of the `FORCE_COLOR` environment variable, as understood by many Node.js tools.
+
+The environment variable is treated as follows:
+
+- if not set or `FORCE_COLOR == ""`: return `None`;
+- if `FORCE_COLOR` is `0` or `false`: return `Some((`[`ColorChoice::Never`]`, `[`ColorDepth::NoColor`]`))`;
+- if `FORCE_COLOR` is `1` or `true`: return `Some((`[`ColorChoice::Always`]`, `[`ColorDepth::Ansi16`]`))`;
+- if `FORCE_COLOR` is `2`: return `Some((`[`ColorChoice::Always`]`, `[`ColorDepth::Ansi256`]`))`;
+- if `FORCE_COLOR` is `3`: return `Some((`[`ColorChoice::Always`]`, `[`ColorDepth::TrueColor`]`))`;
+- otherwise: return `None`.
+*/
+pub fn force_color() -> Option<(ColorChoice, ColorDepth)> {
+    parse_force_color(&crate::env_var(FORCE_COLOR)?)
+}
+
+/// Interpret a value of the `FORCE_COLOR` environment variable.
+pub(crate) fn parse_force_color(value: &str) -> Option<(ColorChoice, ColorDepth)> {
+    match value {
+        "0" | "false" => Some((ColorChoice::Never, ColorDepth::NoColor)),
+        "1" | "true" => Some((ColorChoice::Always, ColorDepth::Ansi16)),
+        "2" => Some((ColorChoice::Always, ColorDepth::Ansi256)),
+        "3" => Some((ColorChoice::Always, ColorDepth::TrueColor)),
+        _ => None,
+    }
+}
+
+/// Guess the color depth from the name of the terminal.
+fn depth_from_term(term: &str) -> ColorDepth {
+    if term == "dumb" {
+        ColorDepth::NoColor
+    } else if term.ends_with("-direct") || term.contains("truecolor") || term.contains("24bit") {
+        ColorDepth::TrueColor
+    } else if term.contains("256") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/**
+Guess the color depth from the `TERM` environment variable.
+
+The environment variable is treated as follows:
+
+- if not set or `TERM == ""`: return `None`;
+- if `TERM == "dumb"`: return `Some(`[`ColorDepth::NoColor`]`)`;
+- if `TERM` ends with `-direct` or contains `truecolor` or `24bit`: return `Some(`[`ColorDepth::TrueColor`]`)`;
+- if `TERM` contains `256` (as in `xterm-256color`): return `Some(`[`ColorDepth::Ansi256`]`)`;
+- otherwise: return `Some(`[`ColorDepth::Ansi16`]`)`.
+*/
+pub fn term() -> Option<ColorDepth> {
+    match crate::env_var(TERM) {
+        Some(term) if !term.is_empty() => Some(depth_from_term(&term)),
+        _ => None,
+    }
+}
+
+/**
+Configurable resolution of the color depth supported by the terminal.
+
+[`color_depth`] is equivalent to `DepthResolver::new().resolve()`.
+
+# Terminal multiplexers
+
+When running inside [tmux](https://github.com/tmux/tmux) or [GNU screen](https://www.gnu.org/software/screen/)
+(see [`terminal::multiplexer`](crate::terminal::multiplexer)), `TERM` describes the multiplexer
+and the variables which identify the terminal emulator are inherited from the outer terminal,
+hence the resolution is adjusted as follows:
+
+- inside tmux, `COLORTERM` is taken into account only if
+  [`trust_colorterm_in_multiplexer`](DepthResolver::trust_colorterm_in_multiplexer) is enabled (the default),
+  the terminal emulator is ignored, and the depth is raised to at least [`ColorDepth::Ansi256`]
+  (unless colors are not supported at all), because tmux always translates the 256-color palette;
+- inside screen, `COLORTERM` and the terminal emulator are ignored,
+  and the depth is lowered to at most [`ColorDepth::Ansi256`].
+
+# SSH sessions
+
+Over SSH (see [`terminal::is_ssh`](crate::terminal::is_ssh)) the capabilities of the local terminal still apply,
+but `COLORTERM` is frequently not forwarded, hence the depth falls back to `TERM`.
+The [`SshPolicy`] controls whether to assume 24-bit colors in this case.
+
+# Interrogation of the terminal
+
+If the <span class="stab portability"><code>probe</code></span> feature is enabled,
+[`probe`](DepthResolver::probe) requests to interrogate the terminal
+(see the [`probe`](crate::probe) module), and the depth is raised to the one confirmed by the terminal.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DepthResolver {
+    trust_colorterm_in_multiplexer: bool,
+    ssh_policy: SshPolicy,
+    #[cfg(feature = "probe")]
+    probe: Option<(ProbeStream, std::time::Duration)>,
+}
+
+/// The stream whose terminal is interrogated by [`DepthResolver::probe`].
+#[cfg(feature = "probe")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ProbeStream {
+    Stdout,
+    Stderr,
+    Stdin,
+}
+
+#[cfg(feature = "probe")]
+impl From<atty::Stream> for ProbeStream {
+    fn from(stream: atty::Stream) -> ProbeStream {
+        match stream {
+            atty::Stream::Stdout => ProbeStream::Stdout,
+            atty::Stream::Stderr => ProbeStream::Stderr,
+            atty::Stream::Stdin => ProbeStream::Stdin,
+        }
+    }
+}
+
+#[cfg(feature = "probe")]
+impl From<ProbeStream> for atty::Stream {
+    fn from(stream: ProbeStream) -> atty::Stream {
+        match stream {
+            ProbeStream::Stdout => atty::Stream::Stdout,
+            ProbeStream::Stderr => atty::Stream::Stderr,
+            ProbeStream::Stdin => atty::Stream::Stdin,
+        }
+    }
+}
+
+/// Policy for the resolution of the color depth inside SSH sessions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum SshPolicy {
+    /// Resolve the depth as in a local session, falling back to `TERM` if `COLORTERM` is not forwarded.
+    #[default]
+    Local,
+    /**
+    Assume 24-bit colors if the depth would otherwise be [`ColorDepth::Ansi256`],
+    since most terminal emulators which support 256 colors also support 24-bit colors.
+    */
+    AssumeTrueColor,
+}
+
+impl Default for DepthResolver {
+    fn default() -> DepthResolver {
+        DepthResolver::new()
+    }
+}
+
+impl DepthResolver {
+    /// Create a resolver with the default settings.
+    pub fn new() -> DepthResolver {
+        DepthResolver {
+            trust_colorterm_in_multiplexer: true,
+            ssh_policy: SshPolicy::Local,
+            #[cfg(feature = "probe")]
+            probe: None,
+        }
+    }
+
+    /**
+    Whether to trust `COLORTERM` inside tmux.
+
+    The variable is usually inherited from the outer terminal,
+    but 24-bit colors are passed through only if tmux is configured accordingly
+    (for instance with `set -as terminal-features ",*:RGB"`).
+    */
+    pub fn trust_colorterm_in_multiplexer(mut self, trust: bool) -> DepthResolver {
+        self.trust_colorterm_in_multiplexer = trust;
+        self
+    }
+
+    /// Set the policy for SSH sessions.
+    pub fn ssh_policy(mut self, policy: SshPolicy) -> DepthResolver {
+        self.ssh_policy = policy;
+        self
+    }
+
+    /**
+    Interrogate the terminal of `stream`, waiting at most `timeout` for its reply
+    (see [`probe::probe`](crate::probe::probe)).
+
+    The interrogation is performed by every call to [`resolve`](DepthResolver::resolve),
+    only if `stream` is a TTY.
+    */
+    #[cfg(feature = "probe")]
+    pub fn probe(mut self, stream: atty::Stream, timeout: std::time::Duration) -> DepthResolver {
+        self.probe = Some((stream.into(), timeout));
+        self
+    }
+
+    /// Determine the color depth supported by the terminal.
+    pub fn resolve(&self) -> ColorDepth {
+        let multiplexer = crate::terminal::multiplexer();
+
+        let depth = match multiplexer {
+            None => colorterm().or_else(|| {
+                crate::terminal::detect()
+                    .supports_truecolor()
+                    .then_some(ColorDepth::TrueColor)
+            }),
+            Some(Multiplexer::Tmux) if self.trust_colorterm_in_multiplexer => colorterm(),
+            Some(_) => None,
+        };
+
+        #[cfg(feature = "terminfo")]
+        let depth = depth.or_else(crate::terminfo::terminfo_depth);
+
+        let depth = depth
+            .or_else(crate::termdb::termdb_depth)
+            .or_else(term)
+            .unwrap_or(if cfg!(windows) {
+                ColorDepth::Ansi16
+            } else {
+                ColorDepth::NoColor
+            });
+
+        // `M-x shell` and `M-x eshell` set `TERM=dumb`, but render ANSI colors.
+        let depth = match crate::terminal::emacs() {
+            Some(emacs) if depth == ColorDepth::NoColor && emacs.renders_ansi() => {
+                ColorDepth::Ansi16
+            }
+            _ => depth,
+        };
+
+        let depth = match self.ssh_policy {
+            SshPolicy::AssumeTrueColor
+                if depth == ColorDepth::Ansi256 && crate::terminal::is_ssh() =>
+            {
+                ColorDepth::TrueColor
+            }
+            _ => depth,
+        };
+
+        let depth = adjust_for_multiplexer(depth, multiplexer);
+
+        #[cfg(feature = "probe")]
+        let depth = match self.probe {
+            Some((stream, timeout)) => crate::probe::probe(stream.into(), timeout)
+                .and_then(|probe| probe.depth())
+                .map_or(depth, |probed| depth.max(probed)),
+            None => depth,
+        };
+
+        depth
+    }
+}
+
+/// Raise or lower the color depth according to the capabilities of the multiplexer.
+fn adjust_for_multiplexer(depth: ColorDepth, multiplexer: Option<Multiplexer>) -> ColorDepth {
+    match multiplexer {
+        Some(Multiplexer::Tmux) if depth != ColorDepth::NoColor => depth.max(ColorDepth::Ansi256),
+        Some(Multiplexer::Screen) => depth.min(ColorDepth::Ansi256),
+        _ => depth,
+    }
+}
+
+/**
+Determine the color depth supported by the terminal.
+
+If no setting is available, the result is [`ColorDepth::Ansi16`] on Windows
+and [`ColorDepth::NoColor`] elsewhere.
+See [`DepthResolver`] for the adjustments performed inside terminal multiplexers and SSH sessions.
+*/
+pub fn color_depth() -> ColorDepth {
+    DepthResolver::new().resolve()
+}
+
+/**
+Resolve both the color choice and the color depth consistently.
+
+The color choice is resolved as in [`resolve`](crate::resolve), including the sources registered with
+[`register_source!`](crate::register_source), but `FORCE_COLOR` (see [`force_color`])
+is also taken into account with the same priority as `CLICOLOR_FORCE`.
+The color depth is then determined as follows:
+
+- if the choice is [`ColorChoice::Never`], the depth is [`ColorDepth::NoColor`];
+- otherwise, the depth is `cli_depth` if given, or else the one requested by `FORCE_COLOR` if set,
+  or else [`color_depth`];
+- if the choice is [`ColorChoice::Always`] or [`ColorChoice::AlwaysAnsi`],
+  the depth is raised to at least [`ColorDepth::Ansi16`].
+
+```
+# use should_color::{depth::{resolve_depth, ColorDepth}, ColorChoice};
+# std::env::remove_var("CLICOLOR_FORCE");
+std::env::set_var("FORCE_COLOR", "3");
+assert_eq!(resolve_depth(None, None), (Some(ColorChoice::Always), ColorDepth::TrueColor));
+
+std::env::remove_var("FORCE_COLOR");
+std::env::set_var("NO_COLOR", "1");
+# std::env::remove_var("CLICOLOR");
+assert_eq!(resolve_depth(None, Some(ColorDepth::Ansi256)), (Some(ColorChoice::Never), ColorDepth::NoColor));
+```
+*/
+pub fn resolve_depth(
+    cli_choice: Option<ColorChoice>,
+    cli_depth: Option<ColorDepth>,
+) -> (Option<ColorChoice>, ColorDepth) {
+    let choice = Resolver::with_order([
+        SourceId::ClicolorForce,
+        SourceId::ForceColor,
+        SourceId::Cli,
+        SourceId::CargoTermColor,
+        SourceId::Clicolor,
+        SourceId::NoColor,
+        SourceId::Terminfo,
+    ])
+    .registered_sources()
+    .resolve(cli_choice);
+
+    let depth = match choice {
+        Some(ColorChoice::Never) => ColorDepth::NoColor,
+        _ => cli_depth
+            .or_else(|| force_color().map(|(_, depth)| depth))
+            .unwrap_or_else(color_depth),
+    };
+
+    let depth = match choice {
+        Some(ColorChoice::Always | ColorChoice::AlwaysAnsi) => depth.max(ColorDepth::Ansi16),
+        _ => depth,
+    };
+
+    (choice, depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantization() {
+        assert_eq!(rgb_to_ansi256(0, 0, 0), 16);
+        assert_eq!(rgb_to_ansi256(255, 255, 255), 231);
+        assert_eq!(rgb_to_ansi256(128, 128, 128), 244);
+        assert_eq!(rgb_to_ansi256(95, 135, 175), 67);
+        for n in 16..=255 {
+            let (r, g, b) = ansi256_to_rgb(n);
+            assert_eq!(ansi256_to_rgb(rgb_to_ansi256(r, g, b)), (r, g, b));
+        }
+        assert_eq!(rgb_to_ansi16(250, 10, 10), 9);
+        assert_eq!(rgb_to_ansi16(20, 20, 200), 4);
+        assert_eq!(ansi256_to_ansi16(196), 9);
+        assert_eq!(
+            ColorDepth::Ansi16.adapt(Color::Fixed(3)),
+            Some(Color::Ansi(3))
+        );
+        assert_eq!(
+            ColorDepth::Ansi256.adapt(Color::Ansi(3)),
+            Some(Color::Ansi(3))
+        );
+    }
+
+    #[test]
+    fn test_from_max_colors() {
+        assert_eq!(ColorDepth::from_max_colors(0), ColorDepth::NoColor);
+        assert_eq!(ColorDepth::from_max_colors(8), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_max_colors(16), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_max_colors(256), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::from_max_colors(1 << 24), ColorDepth::TrueColor);
+    }
+
+    #[test]
+    fn test_depth_from_term() {
+        assert_eq!(depth_from_term("dumb"), ColorDepth::NoColor);
+        assert_eq!(depth_from_term("xterm"), ColorDepth::Ansi16);
+        assert_eq!(depth_from_term("linux"), ColorDepth::Ansi16);
+        assert_eq!(depth_from_term("xterm-256color"), ColorDepth::Ansi256);
+        assert_eq!(depth_from_term("screen-256color"), ColorDepth::Ansi256);
+        assert_eq!(depth_from_term("xterm-direct"), ColorDepth::TrueColor);
+    }
+
+    #[test]
+    fn test_parse_force_color() {
+        assert_eq!(parse_force_color(""), None);
+        assert_eq!(
+            parse_force_color("0"),
+            Some((ColorChoice::Never, ColorDepth::NoColor))
+        );
+        assert_eq!(
+            parse_force_color("true"),
+            Some((ColorChoice::Always, ColorDepth::Ansi16))
+        );
+        assert_eq!(
+            parse_force_color("2"),
+            Some((ColorChoice::Always, ColorDepth::Ansi256))
+        );
+        assert_eq!(
+            parse_force_color("3"),
+            Some((ColorChoice::Always, ColorDepth::TrueColor))
+        );
+        assert_eq!(parse_force_color("4"), None);
+    }
+
+    #[test]
+    fn test_adjust_for_multiplexer() {
+        use ColorDepth::*;
+
+        assert_eq!(adjust_for_multiplexer(Ansi16, None), Ansi16);
+        assert_eq!(
+            adjust_for_multiplexer(Ansi16, Some(Multiplexer::Tmux)),
+            Ansi256
+        );
+        assert_eq!(
+            adjust_for_multiplexer(NoColor, Some(Multiplexer::Tmux)),
+            NoColor
+        );
+        assert_eq!(
+            adjust_for_multiplexer(TrueColor, Some(Multiplexer::Tmux)),
+            TrueColor
+        );
+        assert_eq!(
+            adjust_for_multiplexer(TrueColor, Some(Multiplexer::Screen)),
+            Ansi256
+        );
+        assert_eq!(
+            adjust_for_multiplexer(Ansi16, Some(Multiplexer::Screen)),
+            Ansi16
+        );
+    }
+
+    #[test]
+    fn test_depth_from_colorterm() {
+        for s in ["", "1", "yes", "TrueColor"] {
+            assert_eq!(depth_from_colorterm(s), None);
+        }
+
+        for s in ["truecolor", "24bit"] {
+            assert_eq!(depth_from_colorterm(s), Some(ColorDepth::TrueColor));
+        }
+    }
+}
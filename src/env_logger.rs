@@ -0,0 +1,102 @@
+/*!
+Interoperability with [`env_logger`](https://docs.rs/env_logger/latest/env_logger/).
+
+[`ColorChoice`] can be converted to and from
+[`env_logger::WriteStyle`](https://docs.rs/env_logger/latest/env_logger/enum.WriteStyle.html).
+Moreover [`write_style`] resolves the style of the logger taking into account also the
+[`RUST_LOG_STYLE`] environment variable, so that the same preference controls both
+the output of the application and its logs.
+
+```no_run
+# let cli_color = None;
+env_logger::Builder::from_default_env()
+    .write_style(should_color::env_logger::write_style(cli_color))
+    .init();
+```
+*/
+
+use ::env_logger::WriteStyle;
+
+use crate::{resolve, ColorChoice};
+
+/// Name of the `RUST_LOG_STYLE` environment variable.
+pub const RUST_LOG_STYLE: &str = "RUST_LOG_STYLE";
+
+impl From<ColorChoice> for WriteStyle {
+    fn from(color_choice: ColorChoice) -> WriteStyle {
+        match color_choice {
+            ColorChoice::Never => WriteStyle::Never,
+            ColorChoice::Auto => WriteStyle::Auto,
+            ColorChoice::Always | ColorChoice::AlwaysAnsi => WriteStyle::Always,
+        }
+    }
+}
+
+impl From<WriteStyle> for ColorChoice {
+    fn from(write_style: WriteStyle) -> ColorChoice {
+        match write_style {
+            WriteStyle::Never => ColorChoice::Never,
+            WriteStyle::Auto => ColorChoice::Auto,
+            WriteStyle::Always => ColorChoice::Always,
+        }
+    }
+}
+
+/**
+Get the setting of the `RUST_LOG_STYLE` environment variable.
+
+The environment variable is treated as follows:
+
+- if not set or `RUST_LOG_STYLE == ""` or `RUST_LOG_STYLE` is not one of `"never"`, `"auto"`, `"always"`: return `None`;
+- otherwise: return the corresponding [`ColorChoice`].
+*/
+pub fn rust_log_style() -> Option<ColorChoice> {
+    match crate::env_var(RUST_LOG_STYLE).as_deref() {
+        Some("never") => Some(ColorChoice::Never),
+        Some("auto") => Some(ColorChoice::Auto),
+        Some("always") => Some(ColorChoice::Always),
+        _ => None,
+    }
+}
+
+/**
+Compute an [`env_logger::WriteStyle`](https://docs.rs/env_logger/latest/env_logger/enum.WriteStyle.html)
+suitable for the [`env_logger::Builder::write_style`](https://docs.rs/env_logger/latest/env_logger/struct.Builder.html#method.write_style) setting.
+
+The `RUST_LOG_STYLE` environment variable is treated as a CLI preference
+when `cli` is `None`, hence the resolution order is:
+
+- `CLICOLOR_FORCE` environment variable,
+- explicit user preference `cli`,
+- `RUST_LOG_STYLE` environment variable,
+- `CLICOLOR` environment variable,
+- `NO_COLOR` environment variable,
+- [`WriteStyle::Auto`](https://docs.rs/env_logger/latest/env_logger/enum.WriteStyle.html#variant.Auto).
+*/
+pub fn write_style(cli: Option<ColorChoice>) -> WriteStyle {
+    resolve(cli.or_else(rust_log_style))
+        .unwrap_or(ColorChoice::Auto)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_log_style() {
+        std::env::remove_var(RUST_LOG_STYLE);
+        assert_eq!(rust_log_style(), None);
+
+        for s in ["", "0", "1", "Always", "yes"] {
+            std::env::set_var(RUST_LOG_STYLE, s);
+            assert_eq!(rust_log_style(), None);
+        }
+
+        std::env::set_var(RUST_LOG_STYLE, "never");
+        assert_eq!(rust_log_style(), Some(ColorChoice::Never));
+
+        std::env::set_var(RUST_LOG_STYLE, "always");
+        assert_eq!(rust_log_style(), Some(ColorChoice::Always));
+    }
+}
@@ -0,0 +1,66 @@
+/*!
+Interoperability with [`getopts`](https://docs.rs/getopts/latest/getopts/).
+
+[`add_color_opt`] registers the GNU-style option `--color[=WHEN]` on an
+[`Options`](https://docs.rs/getopts/latest/getopts/struct.Options.html),
+and [`color_from_matches`] parses its value, where a missing `WHEN` means `always`.
+
+```
+use should_color::ColorChoice;
+
+let mut opts = getopts::Options::new();
+should_color::getopts::add_color_opt(&mut opts);
+
+let matches = opts.parse(["--color=never"]).unwrap();
+assert_eq!(should_color::getopts::color_from_matches(&matches), Ok(Some(ColorChoice::Never)));
+
+let matches = opts.parse(["--color"]).unwrap();
+assert_eq!(should_color::getopts::color_from_matches(&matches), Ok(Some(ColorChoice::Always)));
+```
+*/
+
+use ::getopts::{Matches, Options};
+
+use crate::{ColorChoice, ParseColorChoiceError};
+
+/// Name of the option registered by [`add_color_opt`].
+pub const COLOR: &str = "color";
+
+/**
+Register the option `--color[=WHEN]` on `opts`.
+
+Since the value is optional, it must be attached with `=`, as in `--color=never`.
+*/
+pub fn add_color_opt(opts: &mut Options) -> &mut Options {
+    opts.optflagopt("", COLOR, "Coloring of the output", "WHEN")
+}
+
+/**
+Parse the value of the option registered by [`add_color_opt`].
+
+Returns `Ok(None)` if the option is not given, and an error if its value is not a valid [`ColorChoice`].
+*/
+pub fn color_from_matches(matches: &Matches) -> Result<Option<ColorChoice>, ParseColorChoiceError> {
+    matches
+        .opt_default(COLOR, "always")
+        .map(|value| value.parse())
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_from_matches() {
+        let mut opts = Options::new();
+        add_color_opt(&mut opts);
+        let parse = |args: &[&str]| color_from_matches(&opts.parse(args).unwrap());
+        assert_eq!(parse(&[]), Ok(None));
+        assert_eq!(
+            parse(&["--color=auto", "file"]),
+            Ok(Some(ColorChoice::Auto))
+        );
+        assert!(parse(&["--color=sometimes"]).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+/*!
+Color preference from the [git configuration](https://git-scm.com/docs/git-config#Documentation/git-config.txt-colorui).
+*/
+
+use std::process::Command;
+
+use crate::resolver::Source;
+use crate::ColorChoice;
+
+/**
+Source which reads a color preference from the git configuration, such as `color.ui`.
+
+The value is obtained by running `git config --get <key>`,
+and is interpreted with [`ColorChoice::from_git_config_value`].
+If git is not available or the key is not set, the source expresses no preference.
+
+```no_run
+use should_color::git::GitConfigSource;
+use should_color::Resolver;
+
+let resolver = Resolver::new().source(GitConfigSource::new());
+# let cli_color = None;
+let color_choice = resolver.resolve(cli_color);
+```
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitConfigSource {
+    key: String,
+}
+
+impl Default for GitConfigSource {
+    fn default() -> GitConfigSource {
+        GitConfigSource::new()
+    }
+}
+
+impl GitConfigSource {
+    /// Create a source which reads `color.ui`.
+    pub fn new() -> GitConfigSource {
+        GitConfigSource::key("color.ui")
+    }
+
+    /// Create a source which reads an arbitrary `key`, for instance `color.diff`.
+    pub fn key(key: impl Into<String>) -> GitConfigSource {
+        GitConfigSource { key: key.into() }
+    }
+}
+
+impl Source for GitConfigSource {
+    fn get(&self) -> Option<ColorChoice> {
+        let output = Command::new("git")
+            .args(["config", "--get", &self.key])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let value = std::str::from_utf8(&output.stdout).ok()?;
+        ColorChoice::from_git_config_value(value.trim_end_matches(['\n', '\r']))
+    }
+}
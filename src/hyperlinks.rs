@@ -0,0 +1,125 @@
+/*!
+Determine whether output should use [OSC 8 hyperlinks](https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda) or not.
+
+The design mirrors the color resolution of the crate.
+The resulting hyperlink choice is determined by taking into account,
+in order of priority from higher to lower, the following settings:
+
+- `FORCE_HYPERLINK` environment variable,
+- explicit user preference (for instance command line arguments),
+- application default choice.
+
+If the final choice is [`HyperlinkChoice::Auto`] and the feature <span class="stab portability"><code>stream</code></span> is enabled,
+the choice can be refined using [`HyperlinkChoice::for_stream`], which takes into account
+the output stream and the terminal emulator.
+
+# `FORCE_HYPERLINK`
+
+The meaning of the environment variable is the following:
+
+- if not set or `FORCE_HYPERLINK == ""`: ignore;
+- if set and `FORCE_HYPERLINK == "0"`: [`HyperlinkChoice::Never`];
+- if set and `FORCE_HYPERLINK != ""` and `FORCE_HYPERLINK != "0"`: [`HyperlinkChoice::Always`].
+*/
+
+/// Name of the `FORCE_HYPERLINK` environment variable.
+pub const FORCE_HYPERLINK: &str = "FORCE_HYPERLINK";
+
+/// Possible hyperlink choices for the output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HyperlinkChoice {
+    /// The output will not contain hyperlinks.
+    Never,
+    /// The output will contain hyperlinks if the output device is a tty
+    /// and the terminal emulator is known to support them.
+    Auto,
+    /// The output will contain hyperlinks.
+    Always,
+}
+
+#[cfg(feature = "stream")]
+impl HyperlinkChoice {
+    /**
+    Determine the hyperlink setting for a specific stream.
+
+    If the choice is [`HyperlinkChoice::Never`] or [`HyperlinkChoice::Always`],
+    the result will be `false` and `true` respectively.
+
+    If the choice is [`HyperlinkChoice::Auto`], then the answer depends on whether
+    the `stream` is a TTY and the terminal emulator supports hyperlinks
+    according to [`terminal_supports_hyperlinks`].
+    */
+    pub fn for_stream(&self, stream: atty::Stream) -> bool {
+        match self {
+            HyperlinkChoice::Never => false,
+            HyperlinkChoice::Always => true,
+            HyperlinkChoice::Auto => crate::is_tty(stream) && terminal_supports_hyperlinks(),
+        }
+    }
+}
+
+/**
+Get the setting of the `FORCE_HYPERLINK` environment variable.
+
+The environment variable is treated as follows:
+
+- if not set or `FORCE_HYPERLINK == ""`: return `None`;
+- if set and `FORCE_HYPERLINK == "0"`: return `Some(`[`HyperlinkChoice::Never`]`)`;
+- if set and `FORCE_HYPERLINK != ""` and `FORCE_HYPERLINK != "0"`: return `Some(`[`HyperlinkChoice::Always`]`)`.
+*/
+pub fn force_hyperlink() -> Option<HyperlinkChoice> {
+    match crate::env_var(FORCE_HYPERLINK) {
+        Some(s) if s == "0" => Some(HyperlinkChoice::Never),
+        Some(s) if !s.is_empty() => Some(HyperlinkChoice::Always),
+        _ => None,
+    }
+}
+
+/**
+Determine whether the terminal emulator is known to support hyperlinks,
+as identified by [`terminal::detect`](crate::terminal::detect).
+*/
+pub fn terminal_supports_hyperlinks() -> bool {
+    crate::terminal::detect().supports_hyperlinks()
+}
+
+/**
+Resolve the hyperlink choice from the environment variables and an explicit CLI preference.
+
+Commonly this function will be called as `resolve_hyperlinks(cli).unwrap_or(default)`
+to take into account a preference expressed through the CLI arguments
+and the default behavior of the application.
+*/
+pub fn resolve_hyperlinks(cli: Option<HyperlinkChoice>) -> Option<HyperlinkChoice> {
+    force_hyperlink().or(cli)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_force_hyperlink() {
+        std::env::remove_var(FORCE_HYPERLINK);
+        assert_eq!(force_hyperlink(), None);
+        assert_eq!(
+            resolve_hyperlinks(Some(HyperlinkChoice::Auto)),
+            Some(HyperlinkChoice::Auto)
+        );
+
+        std::env::set_var(FORCE_HYPERLINK, "");
+        assert_eq!(force_hyperlink(), None);
+
+        std::env::set_var(FORCE_HYPERLINK, "0");
+        assert_eq!(force_hyperlink(), Some(HyperlinkChoice::Never));
+        assert_eq!(
+            resolve_hyperlinks(Some(HyperlinkChoice::Always)),
+            Some(HyperlinkChoice::Never)
+        );
+
+        for s in ["1", "false", "true"] {
+            std::env::set_var(FORCE_HYPERLINK, s);
+            assert_eq!(force_hyperlink(), Some(HyperlinkChoice::Always));
+        }
+    }
+}
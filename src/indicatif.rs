@@ -0,0 +1,59 @@
+/*!
+Interoperability with [`indicatif`](https://docs.rs/indicatif/latest/indicatif/).
+
+Progress bars are drawn on the standard error, and `indicatif` only checks whether it is a TTY
+before drawing them, while their colors are controlled by the global setting of
+[`console`](https://docs.rs/console/latest/console/).
+This module configures both according to the resolved color choice,
+so that progress bars honor `--color=never` and `NO_COLOR`:
+
+- [`draw_target`] draws progress bars only if the standard error is a TTY;
+- [`apply_indicatif`] enables or disables the colors of the styles of progress bars;
+- [`progress_bar`] creates a progress bar configured with both.
+
+```no_run
+use should_color::{resolve, ColorChoice};
+
+# let cli_color = None;
+let color_choice = resolve(cli_color).unwrap_or(ColorChoice::Auto);
+should_color::indicatif::apply_indicatif(color_choice);
+let bar = indicatif::ProgressBar::with_draw_target(Some(100), should_color::indicatif::draw_target());
+```
+*/
+
+use ::indicatif::{ProgressBar, ProgressDrawTarget};
+
+use crate::{resolve, ColorChoice};
+
+/**
+Create a [`ProgressDrawTarget`](https://docs.rs/indicatif/latest/indicatif/struct.ProgressDrawTarget.html)
+which draws on the standard error if it is a TTY, and is hidden otherwise.
+
+Progress bars are never drawn into pipes and files, regardless of the color choice,
+since their output consists of cursor movements.
+*/
+pub fn draw_target() -> ProgressDrawTarget {
+    if crate::is_tty(atty::Stream::Stderr) {
+        ProgressDrawTarget::stderr()
+    } else {
+        ProgressDrawTarget::hidden()
+    }
+}
+
+/**
+Enable or disable the colors of the styles of progress bars,
+according to the color setting of the standard error as determined by [`ColorChoice::for_stream`].
+*/
+pub fn apply_indicatif(choice: ColorChoice) {
+    console::set_colors_enabled_stderr(choice.for_stream(atty::Stream::Stderr));
+}
+
+/**
+Create a [`ProgressBar`](https://docs.rs/indicatif/latest/indicatif/struct.ProgressBar.html)
+with the given length, drawn on [`draw_target`] and colored according to
+[`resolve`] without an explicit CLI preference and a default value of [`ColorChoice::Auto`].
+*/
+pub fn progress_bar(len: u64) -> ProgressBar {
+    apply_indicatif(resolve(None).unwrap_or(ColorChoice::Auto));
+    ProgressBar::with_draw_target(Some(len), draw_target())
+}
@@ -4,10 +4,15 @@ Determine whether output should use colors or not.
 The resulting color choice is determined by taking into account,
 in order of priority from higher to lower, the following settings:
 
+- thread-local override (see the [`overrides`] module),
 - [`CLICOLOR_FORCE`](#clicolor_force) environment variable (requires the <span class="stab portability"><code>clicolor_force</code></span> feature),
 - explicit user preference (for instance command line arguments),
+- [`CARGO_TERM_COLOR`](#cargo_term_color) environment variable (requires the <span class="stab portability"><code>cargo_term_color</code></span> feature),
 - [`CLICOLOR`](#clicolor) environment variable (requires the <span class="stab portability"><code>clicolor</code></span> feature),
 - [`NO_COLOR`](#no_color) environment variable (requires the <span class="stab portability"><code>no_color</code></span> feature),
+- color capability of the terminal according to the [`terminfo`](mod@terminfo) database (requires the <span class="stab portability"><code>terminfo</code></span> feature),
+  except in the Emacs buffers which render ANSI colors despite `TERM=dumb` (see [`terminal::emacs`]),
+- sources registered by any crate of the application (see the [`registry`] module, requires the <span class="stab portability"><code>inventory</code></span> feature),
 - application default choice.
 
 If the final choice is `ColorChoice::Auto` and the feature <span class="stab portability"><code>stream</code></span> is enabled,
@@ -51,6 +56,17 @@ The meaning of the environment variable is the following:
 - if not set or `NO_COLOR == ""`: ignore;
 - if set and `NO_COLOR != ""`: [`ColorChoice::Never`].
 
+# `CARGO_TERM_COLOR`
+
+Requires the <span class="stab portability" title="Available on crate feature `cargo_term_color` only"><code>cargo_term_color</code></span> feature.
+
+Cargo communicates its color setting to subcommands and build tools through this variable,
+hence `cargo-*` plugins and xtask binaries match the coloring of cargo.
+The meaning of the environment variable is the following:
+
+- if set and `CARGO_TERM_COLOR` is `"never"`, `"auto"`, or `"always"`: the corresponding [`ColorChoice`];
+- otherwise: ignore.
+
 # Compatibility
 
 The goal of this crate is to merge and specify the standards proposed in
@@ -80,9 +96,114 @@ Relevant quote from <https://bixense.com/clicolors/>:
 #![doc = document_features::document_features!(feature_label = r#"<span class="stab portability"><code>{feature}</code></span>"#)]
 //!
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(missing_docs, missing_debug_implementations, warnings)]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+/// Emit a debug event describing the resolution if the `trace` feature is enabled.
+#[allow(unused_macros)]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "trace")]
+        ::log::debug!(target: "should_color", $($arg)*);
+    };
+}
+
+#[cfg(feature = "std")]
+pub mod accessibility;
+#[cfg(feature = "anstyle")]
+pub mod anstyle;
+#[cfg(feature = "capabilities")]
+pub mod capabilities;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "std")]
+pub mod ci;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "std")]
+pub mod depth;
+#[cfg(feature = "env_logger")]
+pub mod env_logger;
+#[cfg(feature = "getopts")]
+pub mod getopts;
+#[cfg(feature = "git")]
+pub mod git;
+#[cfg(feature = "hyperlinks")]
+pub mod hyperlinks;
+#[cfg(feature = "indicatif")]
+pub mod indicatif;
+#[cfg(feature = "std")]
+pub mod markup;
+#[cfg(feature = "miette")]
+pub mod miette;
+#[cfg(feature = "nu-ansi-term")]
+pub mod nu_ansi_term;
+#[cfg(feature = "std")]
+pub mod overrides;
+#[cfg(feature = "std")]
+pub mod pager;
+#[cfg(feature = "stream")]
+pub mod policy;
+#[cfg(feature = "probe")]
+pub mod probe;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(any(feature = "query", feature = "probe"))]
+mod query;
+#[cfg(feature = "inventory")]
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod report;
+#[cfg(feature = "std")]
+pub mod resolver;
+#[cfg(feature = "simplelog")]
+pub mod simplelog;
+#[cfg(feature = "std")]
+pub mod snapshot;
+#[cfg(feature = "std")]
+pub mod sources;
+pub mod spec;
+#[cfg(feature = "std")]
+pub mod strip;
+#[cfg(feature = "style")]
+pub mod style;
+pub mod styler;
+#[cfg(feature = "stream")]
+pub mod supports_color;
+#[cfg(feature = "termcolor")]
+pub mod termcolor;
+#[cfg(feature = "std")]
+pub mod termdb;
+#[cfg(feature = "std")]
+pub mod terminal;
+#[cfg(feature = "terminfo")]
+pub mod terminfo;
+#[cfg(feature = "test_util")]
+pub mod test_util;
+#[cfg(feature = "theme")]
+pub mod theme;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+#[cfg(feature = "unicode")]
+pub mod unicode;
+#[cfg(feature = "windows")]
+pub mod windows;
+
+#[cfg(feature = "std")]
+pub use resolver::{EnvVarSource, Resolver, Source, SourceId};
+#[cfg(feature = "std")]
+pub use snapshot::EnvSnapshot;
+
+#[cfg(feature = "std")]
+use std::sync::PoisonError;
+
+#[cfg(feature = "stream")]
+#[doc(hidden)]
+pub use atty as __atty;
+
 /// Name of the `NO_COLOR` environment variable.
 #[cfg(feature = "no_color")]
 pub const NO_COLOR: &str = "NO_COLOR";
@@ -92,6 +213,9 @@ pub const CLICOLOR: &str = "CLICOLOR";
 /// Name of the `CLICOLOR_FORCE` environment variable.
 #[cfg(feature = "clicolor_force")]
 pub const CLICOLOR_FORCE: &str = "CLICOLOR_FORCE";
+/// Name of the `CARGO_TERM_COLOR` environment variable.
+#[cfg(feature = "cargo_term_color")]
+pub const CARGO_TERM_COLOR: &str = "CARGO_TERM_COLOR";
 
 /**
 Possible color choices for the output.
@@ -104,7 +228,8 @@ Possible color choices for the output.
 
 If the <span class="stab portability" title="Available on crate feature `clap` only"><code>clap</code></span> feature is enabled then
 [`ColorChoice`] can be converted to and from [`clap::ColorChoice`](https://docs.rs/clap/latest/clap/enum.ColorChoice.html).
-Moreover it implements [`clap::ValueEnum`](https://docs.rs/clap/latest/clap/trait.ValueEnum.html), hence can be used as
+Moreover it implements [`clap::ValueEnum`](https://docs.rs/clap/latest/clap/trait.ValueEnum.html),
+accepting also the aliases `no`, `tty`, `if-tty`, `yes`, and `force`, hence can be used as
 
 ```rust
 #[derive(clap::Parser)]
@@ -118,8 +243,36 @@ struct Cli {
 ```
 "#
 )]
+#[cfg_attr(
+    feature = "argh",
+    doc = r#"
+
+# Argh interoperability
+
+If the <span class="stab portability" title="Available on crate feature `argh` only"><code>argh</code></span> feature is enabled then
+[`ColorChoice`] implements [`argh::FromArgValue`](https://docs.rs/argh/latest/argh/trait.FromArgValue.html)
+(by means of its [`FromStr`](std::str::FromStr) implementation), hence can be used as
+
+```rust
+#[derive(argh::FromArgs)]
+/// Top-level command.
+struct Cli {
+    /// coloring of the output
+    #[argh(option, arg_name = "WHEN")]
+    color: Option<should_color::ColorChoice>,
+
+    // Other arguments...
+}
+```
+"#
+)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "python", pyo3::pyclass(eq, eq_int, from_py_object))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum ColorChoice {
     /// The output will not be colorized.
     Never,
@@ -128,6 +281,296 @@ pub enum ColorChoice {
     Auto,
     /// The output will be colorized.
     Always,
+    /**
+    The output will be colorized with ANSI escape sequences,
+    even on legacy Windows consoles which would otherwise be driven through the console API.
+
+    This is useful to pipe the output into tools which understand ANSI escape sequences.
+    On other platforms it is equivalent to [`ColorChoice::Always`].
+    */
+    AlwaysAnsi,
+}
+
+/// The default choice is [`ColorChoice::Auto`].
+impl Default for ColorChoice {
+    fn default() -> ColorChoice {
+        ColorChoice::Auto
+    }
+}
+
+impl ColorChoice {
+    /**
+    Combine two choices, keeping the one which enables colors more eagerly.
+
+    The choices are ordered as `Never < Auto < Always < AlwaysAnsi`.
+
+    ```
+    # use should_color::ColorChoice;
+    assert_eq!(ColorChoice::Never.strongest(ColorChoice::Auto), ColorChoice::Auto);
+    assert_eq!(ColorChoice::Always.strongest(ColorChoice::Auto), ColorChoice::Always);
+    ```
+    */
+    pub fn strongest(self, other: ColorChoice) -> ColorChoice {
+        self.max(other)
+    }
+
+    /**
+    Combine two choices, keeping the one which enables colors more reluctantly.
+
+    The choices are ordered as `Never < Auto < Always < AlwaysAnsi`.
+
+    ```
+    # use should_color::ColorChoice;
+    assert_eq!(ColorChoice::Never.weakest(ColorChoice::Auto), ColorChoice::Never);
+    assert_eq!(ColorChoice::Always.weakest(ColorChoice::Auto), ColorChoice::Auto);
+    ```
+    */
+    pub fn weakest(self, other: ColorChoice) -> ColorChoice {
+        self.min(other)
+    }
+
+    /**
+    Return `self`, unless it is [`ColorChoice::Auto`] and `other` expresses an explicit choice.
+
+    This allows a subsystem which has no opinion of its own to defer to another one.
+
+    ```
+    # use should_color::ColorChoice;
+    assert_eq!(ColorChoice::Auto.or(Some(ColorChoice::Never)), ColorChoice::Never);
+    assert_eq!(ColorChoice::Always.or(Some(ColorChoice::Never)), ColorChoice::Always);
+    assert_eq!(ColorChoice::Auto.or(None), ColorChoice::Auto);
+    ```
+    */
+    pub fn or(self, other: Option<ColorChoice>) -> ColorChoice {
+        match self {
+            ColorChoice::Auto => other.unwrap_or(self),
+            _ => self,
+        }
+    }
+
+    /**
+    Get the default choice configured when the crate was compiled.
+
+    The default is read from the environment variable `SHOULD_COLOR_DEFAULT` at build time,
+    which accepts the same values as [`FromStr`](core::str::FromStr) (`never`, `auto`, `always`, `always-ansi`,
+    and their aliases); if it is unset or empty, the default is [`ColorChoice::Auto`].
+    An invalid value is a compilation error.
+
+    This allows packagers to change the default of an application without modifying its code,
+    for example with `SHOULD_COLOR_DEFAULT=never cargo build`,
+    provided that the application uses this function as the fallback of the resolution.
+
+    ```
+    # use should_color::{resolve, ColorChoice};
+    # let cli_color = None;
+    let choice = resolve(cli_color).unwrap_or(ColorChoice::compiled_default());
+    ```
+    */
+    pub const fn compiled_default() -> ColorChoice {
+        COMPILED_DEFAULT
+    }
+
+    /**
+    Translate the boolean flags of a command line into a color choice.
+
+    The flags are interpreted as follows, the first matching rule determining the result:
+
+    - `no_color` is `true` (e.g. `--no-color` was given): return `Some(`[`ColorChoice::Never`]`)`;
+    - `color` is `Some(true)` (e.g. `--color` was given): return `Some(`[`ColorChoice::Always`]`)`;
+    - `color` is `Some(false)`: return `Some(`[`ColorChoice::Never`]`)`;
+    - otherwise: return `None`, so that the choice is left to the environment.
+
+    Notice that disabling colors takes precedence, as the relative order of the flags is unknown.
+
+    ```
+    # use should_color::ColorChoice;
+    assert_eq!(ColorChoice::from_flags(Some(true), true), Some(ColorChoice::Never));
+    assert_eq!(ColorChoice::from_flags(Some(true), false), Some(ColorChoice::Always));
+    assert_eq!(ColorChoice::from_flags(None, false), None);
+    ```
+    */
+    pub fn from_flags(color: Option<bool>, no_color: bool) -> Option<ColorChoice> {
+        if no_color {
+            Some(ColorChoice::Never)
+        } else {
+            color.map(ColorChoice::from)
+        }
+    }
+}
+
+/**
+Convert a boolean into a color choice: `true` is [`ColorChoice::Always`] and `false` is [`ColorChoice::Never`].
+
+```
+# use should_color::ColorChoice;
+assert_eq!(ColorChoice::from(true), ColorChoice::Always);
+assert_eq!(ColorChoice::from(false), ColorChoice::Never);
+```
+*/
+impl From<bool> for ColorChoice {
+    fn from(enabled: bool) -> ColorChoice {
+        if enabled {
+            ColorChoice::Always
+        } else {
+            ColorChoice::Never
+        }
+    }
+}
+
+/**
+A [`ColorChoice`] which can be shared between threads without a lock.
+
+Internally it is an [`AtomicU8`](core::sync::atomic::AtomicU8), and the methods take the same
+[`Ordering`](core::sync::atomic::Ordering) arguments as the atomic types of the standard library.
+It is available also without the standard library.
+
+```
+use core::sync::atomic::Ordering;
+use should_color::{AtomicColorChoice, ColorChoice};
+
+static CHOICE: AtomicColorChoice = AtomicColorChoice::new(ColorChoice::Auto);
+
+CHOICE.store(ColorChoice::Never, Ordering::Relaxed);
+assert_eq!(CHOICE.load(Ordering::Relaxed), ColorChoice::Never);
+assert_eq!(
+    CHOICE.compare_exchange(ColorChoice::Auto, ColorChoice::Always, Ordering::Relaxed, Ordering::Relaxed),
+    Err(ColorChoice::Never)
+);
+```
+*/
+#[derive(Debug)]
+pub struct AtomicColorChoice {
+    value: core::sync::atomic::AtomicU8,
+}
+
+impl AtomicColorChoice {
+    /// The encoding of the absence of a choice, used by the crate for optional choices.
+    const UNSET: u8 = u8::MAX;
+
+    /// Create a new atomic color choice.
+    pub const fn new(choice: ColorChoice) -> AtomicColorChoice {
+        AtomicColorChoice {
+            value: core::sync::atomic::AtomicU8::new(choice as u8),
+        }
+    }
+
+    /// Create an atomic which holds no choice, as read by [`AtomicColorChoice::load_option`].
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) const fn unset() -> AtomicColorChoice {
+        AtomicColorChoice {
+            value: core::sync::atomic::AtomicU8::new(AtomicColorChoice::UNSET),
+        }
+    }
+
+    /// Decode a value stored in the atomic.
+    const fn decode(value: u8) -> Option<ColorChoice> {
+        match value {
+            0 => Some(ColorChoice::Never),
+            1 => Some(ColorChoice::Auto),
+            2 => Some(ColorChoice::Always),
+            3 => Some(ColorChoice::AlwaysAnsi),
+            _ => None,
+        }
+    }
+
+    /// Decode a value which is known to be a choice, since only the crate stores [`AtomicColorChoice::UNSET`].
+    fn decode_choice(value: u8) -> ColorChoice {
+        AtomicColorChoice::decode(value).unwrap_or_default()
+    }
+
+    /// Load the color choice.
+    pub fn load(&self, order: core::sync::atomic::Ordering) -> ColorChoice {
+        AtomicColorChoice::decode_choice(self.value.load(order))
+    }
+
+    /// Store a color choice.
+    pub fn store(&self, choice: ColorChoice, order: core::sync::atomic::Ordering) {
+        self.value.store(choice as u8, order);
+    }
+
+    /// Store a color choice, returning the previous one.
+    pub fn swap(&self, choice: ColorChoice, order: core::sync::atomic::Ordering) -> ColorChoice {
+        AtomicColorChoice::decode_choice(self.value.swap(choice as u8, order))
+    }
+
+    /**
+    Store `new` if the current choice is `current`.
+
+    The result is `Ok` with the previous choice if it was replaced, and `Err` with the current choice otherwise.
+    */
+    pub fn compare_exchange(
+        &self,
+        current: ColorChoice,
+        new: ColorChoice,
+        success: core::sync::atomic::Ordering,
+        failure: core::sync::atomic::Ordering,
+    ) -> Result<ColorChoice, ColorChoice> {
+        self.value
+            .compare_exchange(current as u8, new as u8, success, failure)
+            .map(AtomicColorChoice::decode_choice)
+            .map_err(AtomicColorChoice::decode_choice)
+    }
+
+    /// Consume the atomic and return the color choice.
+    pub fn into_inner(self) -> ColorChoice {
+        AtomicColorChoice::decode_choice(self.value.into_inner())
+    }
+
+    /// Load the color choice, or `None` if the atomic holds no choice.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) fn load_option(&self, order: core::sync::atomic::Ordering) -> Option<ColorChoice> {
+        AtomicColorChoice::decode(self.value.load(order))
+    }
+
+    /// Store a color choice, or the absence of a choice.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) fn store_option(
+        &self,
+        choice: Option<ColorChoice>,
+        order: core::sync::atomic::Ordering,
+    ) {
+        let value = choice.map_or(AtomicColorChoice::UNSET, |choice| choice as u8);
+        self.value.store(value, order);
+    }
+}
+
+/// The default choice is [`ColorChoice::Auto`].
+impl Default for AtomicColorChoice {
+    fn default() -> AtomicColorChoice {
+        AtomicColorChoice::new(ColorChoice::default())
+    }
+}
+
+impl From<ColorChoice> for AtomicColorChoice {
+    fn from(choice: ColorChoice) -> AtomicColorChoice {
+        AtomicColorChoice::new(choice)
+    }
+}
+
+impl From<AtomicColorChoice> for ColorChoice {
+    fn from(atomic: AtomicColorChoice) -> ColorChoice {
+        atomic.into_inner()
+    }
+}
+
+/// The value of `SHOULD_COLOR_DEFAULT` at build time, evaluated at compile time to reject invalid values.
+const COMPILED_DEFAULT: ColorChoice = match option_env!("SHOULD_COLOR_DEFAULT") {
+    Some(value) => match parse_compiled_default(value.as_bytes()) {
+        Some(choice) => choice,
+        None => panic!("invalid value of SHOULD_COLOR_DEFAULT"),
+    },
+    None => ColorChoice::Auto,
+};
+
+/// Parse the value of `SHOULD_COLOR_DEFAULT`, where the empty value means [`ColorChoice::Auto`].
+const fn parse_compiled_default(value: &[u8]) -> Option<ColorChoice> {
+    match value {
+        b"never" | b"no" => Some(ColorChoice::Never),
+        b"auto" | b"tty" | b"if-tty" | b"" => Some(ColorChoice::Auto),
+        b"always" | b"yes" | b"force" => Some(ColorChoice::Always),
+        b"always-ansi" => Some(ColorChoice::AlwaysAnsi),
+        _ => None,
+    }
 }
 
 #[cfg(feature = "stream")]
@@ -135,11 +578,13 @@ impl ColorChoice {
     /**
     Determine the color setting for a specific stream.
 
-    If the choice is [`ColorChoice::Never`] or [`ColorChoice::Always`],
+    If the choice is [`ColorChoice::Never`] or [`ColorChoice::Always`] (or [`ColorChoice::AlwaysAnsi`]),
     the result will be `false` and `true` respectively.
 
     If the choice is [`ColorChoice::Auto`], then the answer depends on whether
-    the `stream` is a TTY or not.
+    the `stream` is a TTY or not, unless the global [`AutoPolicy`](policy::AutoPolicy) specifies otherwise.
+    If the <span class="stab portability"><code>colorless_term</code></span> feature is enabled,
+    colors are disabled on terminals without color capability (see [`termdb::is_colorless`]).
 
     See the examples [`colored.rs`] and [`termcolor.rs`] for a demonstration of how to use this method.
 
@@ -147,25 +592,241 @@ impl ColorChoice {
     [`termcolor.rs`]: https://github.com/FedericoStra/should-color/blob/master/examples/termcolor.rs#L38-L39
     */
     pub fn for_stream(&self, stream: atty::Stream) -> bool {
+        self.for_stream_with_policy(stream, policy::AutoPolicy::global())
+    }
+}
+
+/**
+Whether the `stream` is a TTY.
+
+On WASI the check is performed by the standard library, since `atty` supports only Unix and Windows;
+on other targets without a TTY model, such as `wasm32-unknown-unknown`, the result is always `false`.
+*/
+#[cfg(feature = "stream")]
+pub(crate) fn is_tty(stream: atty::Stream) -> bool {
+    #[cfg(target_os = "wasi")]
+    {
+        use std::io::IsTerminal;
+
+        match stream {
+            atty::Stream::Stdout => std::io::stdout().is_terminal(),
+            atty::Stream::Stderr => std::io::stderr().is_terminal(),
+            atty::Stream::Stdin => std::io::stdin().is_terminal(),
+        }
+    }
+    #[cfg(not(target_os = "wasi"))]
+    atty::is(stream)
+}
+
+#[cfg(feature = "std")]
+impl ColorChoice {
+    /**
+    Determine the color setting for an arbitrary file descriptor, such as a socket or a pipe.
+
+    This is the same as [`ColorChoice::for_stream`], but [`ColorChoice::Auto`] is resolved by checking
+    whether `fd` refers to a terminal.
+
+    ```
+    # use should_color::ColorChoice;
+    let file = std::fs::File::open("Cargo.toml").unwrap();
+    assert!(!ColorChoice::Auto.for_fd(&file));
+    assert!(ColorChoice::Always.for_fd(&file));
+    ```
+    */
+    #[cfg(unix)]
+    pub fn for_fd(&self, fd: impl std::os::fd::AsFd) -> bool {
+        use std::io::IsTerminal;
+
+        match self {
+            ColorChoice::Never => false,
+            ColorChoice::Always | ColorChoice::AlwaysAnsi => true,
+            ColorChoice::Auto => fd.as_fd().is_terminal(),
+        }
+    }
+
+    /**
+    Determine the color setting for an arbitrary handle, such as a pipe or a console.
+
+    This is the same as [`ColorChoice::for_stream`], but [`ColorChoice::Auto`] is resolved by checking
+    whether `handle` refers to a terminal.
+    */
+    #[cfg(windows)]
+    pub fn for_handle(&self, handle: impl std::os::windows::io::AsHandle) -> bool {
+        use std::io::IsTerminal;
+
         match self {
             ColorChoice::Never => false,
-            ColorChoice::Always => true,
-            ColorChoice::Auto => atty::is(stream),
+            ColorChoice::Always | ColorChoice::AlwaysAnsi => true,
+            ColorChoice::Auto => handle.as_handle().is_terminal(),
+        }
+    }
+}
+
+/**
+Parse a [`ColorChoice`] from one of the strings `"never"`, `"auto"`, `"always"`, or `"always-ansi"`,
+or from one of the common aliases `"no"` (never), `"tty"` and `"if-tty"` (auto), `"yes"` and `"force"` (always).
+
+The empty string is rejected: the GNU convention that `--color` without a value means `--color=always`
+is handled by the argument parsers, such as `clap_color_arg` and [`scan_args`],
+rather than by the parsing of the value.
+
+```
+# use should_color::ColorChoice;
+assert_eq!("auto".parse(), Ok(ColorChoice::Auto));
+assert_eq!("if-tty".parse(), Ok(ColorChoice::Auto));
+assert!("".parse::<ColorChoice>().is_err());
+assert!("sometimes".parse::<ColorChoice>().is_err());
+```
+*/
+#[cfg(feature = "std")]
+impl std::str::FromStr for ColorChoice {
+    type Err = ParseColorChoiceError;
+
+    fn from_str(s: &str) -> Result<ColorChoice, ParseColorChoiceError> {
+        match s {
+            "never" | "no" => Ok(ColorChoice::Never),
+            "auto" | "tty" | "if-tty" => Ok(ColorChoice::Auto),
+            "always" | "yes" | "force" => Ok(ColorChoice::Always),
+            "always-ansi" => Ok(ColorChoice::AlwaysAnsi),
+            _ => Err(ParseColorChoiceError {
+                value: s.to_owned(),
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ColorChoice {
+    /**
+    Interpret a value of the git configuration, such as `color.ui`.
+
+    The value is interpreted as git does:
+
+    - `never`, and the false booleans `false`, `no`, `off`, `0`, `""`: return `Some(`[`ColorChoice::Never`]`)`;
+    - `auto`, and the true booleans `true`, `yes`, `on`, and the nonzero integers: return `Some(`[`ColorChoice::Auto`]`)`;
+    - `always`: return `Some(`[`ColorChoice::Always`]`)`;
+    - any other value: return `None`.
+
+    All the keywords are case-insensitive.
+    Notice in particular that `true` means [`ColorChoice::Auto`], as in git since version 1.8.4,
+    and that an empty value means [`ColorChoice::Never`].
+
+    ```
+    # use should_color::ColorChoice;
+    assert_eq!(ColorChoice::from_git_config_value("true"), Some(ColorChoice::Auto));
+    assert_eq!(ColorChoice::from_git_config_value("always"), Some(ColorChoice::Always));
+    assert_eq!(ColorChoice::from_git_config_value("Off"), Some(ColorChoice::Never));
+    ```
+    */
+    pub fn from_git_config_value(value: &str) -> Option<ColorChoice> {
+        match value.to_ascii_lowercase().as_str() {
+            "never" | "false" | "no" | "off" | "" => Some(ColorChoice::Never),
+            "auto" | "true" | "yes" | "on" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            integer => match integer.parse::<i64>().ok()? {
+                0 => Some(ColorChoice::Never),
+                _ => Some(ColorChoice::Auto),
+            },
+        }
+    }
+
+    /**
+    Propagate the color choice to a child process through its environment variables.
+
+    - [`ColorChoice::Never`]: set `NO_COLOR=1` and remove `CLICOLOR_FORCE`;
+    - [`ColorChoice::Always`] and [`ColorChoice::AlwaysAnsi`]: set `CLICOLOR_FORCE=1` and remove `NO_COLOR`;
+    - [`ColorChoice::Auto`]: remove both, so that the child decides according to its own output.
+
+    This allows wrappers, such as task runners and test harnesses,
+    to make their subprocesses follow the `--color` decision of the parent.
+
+    ```no_run
+    # use should_color::ColorChoice;
+    # let cli_color = None;
+    let choice = should_color::resolve(cli_color).unwrap_or(ColorChoice::Auto);
+    let mut command = std::process::Command::new("cargo");
+    choice.apply_to_command(&mut command);
+    command.arg("build").status()?;
+    # Ok::<(), std::io::Error>(())
+    ```
+    */
+    pub fn apply_to_command(&self, command: &mut std::process::Command) {
+        match self {
+            ColorChoice::Never => {
+                command.env("NO_COLOR", "1").env_remove("CLICOLOR_FORCE");
+            }
+            ColorChoice::Auto => {
+                command.env_remove("NO_COLOR").env_remove("CLICOLOR_FORCE");
+            }
+            ColorChoice::Always | ColorChoice::AlwaysAnsi => {
+                command.env("CLICOLOR_FORCE", "1").env_remove("NO_COLOR");
+            }
         }
     }
 }
 
+/// Error returned when parsing a [`ColorChoice`] from an invalid string.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseColorChoiceError {
+    value: String,
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for ParseColorChoiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid color choice {:?} (expected one of \"never\", \"auto\", \"always\", \"always-ansi\")",
+            self.value
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseColorChoiceError {}
+
 // #[cfg(feature = "clap")]
 // /// Alias for [`clap::ColorChoice`](https://docs.rs/clap/latest/clap/enum.ColorChoice.html).
 // pub type ClapColorChoice = clap::ColorChoice;
 
+/// The possible values are `never`, `auto`, `always`, and `always-ansi`, with the same aliases as [`FromStr`](std::str::FromStr).
+#[cfg(feature = "clap")]
+impl clap::ValueEnum for ColorChoice {
+    fn value_variants<'a>() -> &'a [ColorChoice] {
+        &[
+            ColorChoice::Never,
+            ColorChoice::Auto,
+            ColorChoice::Always,
+            ColorChoice::AlwaysAnsi,
+        ]
+    }
+
+    fn to_possible_value<'a>(&self) -> Option<clap::PossibleValue<'a>> {
+        let value = match self {
+            ColorChoice::Never => clap::PossibleValue::new("never")
+                .alias("no")
+                .help("Never colorize the output (alias: no)"),
+            ColorChoice::Auto => clap::PossibleValue::new("auto")
+                .aliases(["tty", "if-tty"])
+                .help("Colorize the output if it is a tty (aliases: tty, if-tty)"),
+            ColorChoice::Always => clap::PossibleValue::new("always")
+                .aliases(["yes", "force"])
+                .help("Always colorize the output (aliases: yes, force)"),
+            ColorChoice::AlwaysAnsi => clap::PossibleValue::new("always-ansi")
+                .help("Always colorize the output with ANSI escape sequences"),
+        };
+        Some(value)
+    }
+}
+
 #[cfg(feature = "clap")]
 impl From<ColorChoice> for clap::ColorChoice {
     fn from(color_choice: ColorChoice) -> clap::ColorChoice {
         match color_choice {
             ColorChoice::Never => clap::ColorChoice::Never,
             ColorChoice::Auto => clap::ColorChoice::Auto,
-            ColorChoice::Always => clap::ColorChoice::Always,
+            ColorChoice::Always | ColorChoice::AlwaysAnsi => clap::ColorChoice::Always,
         }
     }
 }
@@ -202,76 +863,561 @@ pub fn clap_color() -> clap::ColorChoice {
 }
 
 /**
-Get the setting of the `NO_COLOR` environment variable.
+Create a global [`clap::Arg`](https://docs.rs/clap/latest/clap/builder/struct.Arg.html)
+for the GNU-style option `--color[=WHEN]`.
 
-The environment variable is treated as follows:
+The option accepts the forms `--color`, `--color=WHEN`, and `--color WHEN`,
+where a missing `WHEN` means `always`.
+Its value can be retrieved as an `Option<ColorChoice>` with `ArgMatches::get_one`.
 
-- if not set or `NO_COLOR == ""`: return `None`;
-- if set and `NO_COLOR != ""`: return `Some(`[`ColorChoice::Never`]`)`.
-*/
-#[cfg(feature = "no_color")]
-pub fn no_color() -> Option<ColorChoice> {
-    match std::env::var_os(NO_COLOR) {
-        Some(s) if !s.is_empty() => Some(ColorChoice::Never),
-        _ => None,
-    }
-}
+Notice that, since the value is optional, `--color` followed by a positional argument
+is parsed as `--color WHEN`, hence positional arguments should follow `--color=WHEN` or `--`.
 
-/**
-Get the setting of the `CLICOLOR` environment variable.
+```rust
+use should_color::{clap_color_arg, ColorChoice};
 
-The environment variable is treated as follows:
+let cmd = clap::Command::new("prog").arg(clap_color_arg());
 
-- if not set or `CLICOLOR == ""`: return `None`;
-- if set and `CLICOLOR == "0"`: return `Some(`[`ColorChoice::Never`]`)`;
-- if set and `CLICOLOR != ""` and `CLICOLOR != "0"`: return `Some(`[`ColorChoice::Auto`]`)`.
+let matches = cmd.clone().get_matches_from(["prog", "--color"]);
+assert_eq!(matches.get_one::<ColorChoice>("color"), Some(&ColorChoice::Always));
+
+let matches = cmd.get_matches_from(["prog", "--color", "never"]);
+assert_eq!(matches.get_one::<ColorChoice>("color"), Some(&ColorChoice::Never));
+```
+
+With the derive API, the equivalent is
+
+```rust
+#[derive(clap::Parser)]
+struct Cli {
+    /// Coloring of the output
+    #[clap(
+        long,
+        value_name = "WHEN",
+        arg_enum,
+        global = true,
+        min_values = 0,
+        default_missing_value = "always"
+    )]
+    color: Option<should_color::ColorChoice>,
+}
+```
 */
-#[cfg(feature = "clicolor")]
-pub fn clicolor() -> Option<ColorChoice> {
-    match std::env::var_os(CLICOLOR) {
-        Some(s) if s == "0" => Some(ColorChoice::Never),
-        Some(s) if !s.is_empty() => Some(ColorChoice::Auto),
-        _ => None,
-    }
+#[cfg(feature = "clap")]
+pub fn clap_color_arg() -> clap::Arg<'static> {
+    clap::Arg::new("color")
+        .long("color")
+        .value_name("WHEN")
+        .help("Coloring of the output")
+        .value_parser(clap::value_parser!(ColorChoice))
+        .global(true)
+        .min_values(0)
+        .max_values(1)
+        .default_missing_value("always")
 }
 
 /**
-Get the setting of the `CLICOLOR_FORCE` environment variable.
+Add the option `--color[=WHEN]` given by [`clap_color_arg`] to a command built with the builder API of `clap`.
 
-The environment variable is treated as follows:
+The option is global, hence it is accepted also by all the subcommands.
+Its value is retrieved with [`color_from_matches`].
 
-- if not set or `CLICOLOR_FORCE == ""` or `CLICOLOR_FORCE == "0"`: return `None`;
-- if set and `CLICOLOR_FORCE != ""` and `CLICOLOR_FORCE != "0"`: return `Some`[`ColorChoice::Always`]`)`.
+```rust
+use should_color::{add_color_arg, color_from_matches, ColorChoice};
+
+let cmd = add_color_arg(clap::Command::new("prog")).subcommand(clap::Command::new("sub"));
+
+let matches = cmd.get_matches_from(["prog", "sub", "--color=never"]);
+assert_eq!(color_from_matches(&matches), Some(ColorChoice::Never));
+```
 */
-#[cfg(feature = "clicolor_force")]
-pub fn clicolor_force() -> Option<ColorChoice> {
-    match std::env::var_os(CLICOLOR_FORCE) {
-        Some(s) if !s.is_empty() && s != "0" => Some(ColorChoice::Always),
-        _ => None,
-    }
+#[cfg(feature = "clap")]
+pub fn add_color_arg(cmd: clap::Command<'static>) -> clap::Command<'static> {
+    cmd.arg(clap_color_arg())
 }
 
 /**
-Resolve the output color choice from the environment variables and an explicit CLI preference.
+Create a global [`clap::Arg`](https://docs.rs/clap/latest/clap/builder/struct.Arg.html)
+for the option `--color[=WHEN]`, where `WHEN` may differ per stream (see [`StreamColorChoice`]).
 
-Notice that the resolution depends on the activation of the features
-<span class="stab portability"><code>clicolor_force</code></span>,
-<span class="stab portability"><code>clicolor</code></span>, and
-<span class="stab portability"><code>no_color</code></span>.
-Please refer to the [crate level documentation](crate) for a detailed description of the
-resolution process.
+The option accepts the same forms as [`clap_color_arg`], and additionally per-stream values
+such as `--color=stdout=never,stderr=always`.
+Its value is retrieved with [`stream_color_from_matches`].
 
-Commonly this function will be called as `resolve(cli).unwrap_or(default)` to take into account
-a preference expressed through the CLI arguments and the default behavior of the application.
-See the examples [`colored.rs`] and [`termcolor.rs`] for a demonstration of how to use this function.
+```rust
+use should_color::{clap_stream_color_arg, stream_color_from_matches, ColorChoice};
 
-[`colored.rs`]: https://github.com/FedericoStra/should-color/blob/master/examples/colored.rs#L36
-[`termcolor.rs`]: https://github.com/FedericoStra/should-color/blob/master/examples/termcolor.rs#L36
+let cmd = clap::Command::new("prog").arg(clap_stream_color_arg());
 
-# Examples
+let matches = cmd.clone().get_matches_from(["prog", "--color=stdout=never,stderr=always"]);
+let choice = stream_color_from_matches(&matches).unwrap();
+assert_eq!(choice.stdout, Some(ColorChoice::Never));
+assert_eq!(choice.stderr, Some(ColorChoice::Always));
 
-The following examples assume that all the features
-<span class="stab portability" title="Available on crate feature `clicolor` only"><code>clicolor</code></span>,
+let matches = cmd.get_matches_from(["prog", "--color"]);
+assert_eq!(stream_color_from_matches(&matches), Some(ColorChoice::Always.into()));
+```
+*/
+#[cfg(feature = "clap")]
+pub fn clap_stream_color_arg() -> clap::Arg<'static> {
+    clap_color_arg()
+        .value_name("[STREAM=]WHEN")
+        .value_parser(clap::value_parser!(StreamColorChoice))
+}
+
+/**
+Get the value of the option added by [`clap_stream_color_arg`].
+
+The subcommands are searched as well, and the value given to the innermost one takes precedence.
+*/
+#[cfg(feature = "clap")]
+pub fn stream_color_from_matches(matches: &clap::ArgMatches) -> Option<StreamColorChoice> {
+    let mut choice = None;
+    let mut matches = Some(matches);
+    while let Some(current) = matches {
+        if let Ok(Some(&value)) = current.try_get_one::<StreamColorChoice>("color") {
+            choice = Some(value);
+        }
+        matches = current.subcommand().map(|(_, sub)| sub);
+    }
+    choice
+}
+
+/**
+Get the value of the option added by [`add_color_arg`] or [`clap_color_arg`].
+
+The subcommands are searched as well, and the value given to the innermost one takes precedence.
+*/
+#[cfg(feature = "clap")]
+pub fn color_from_matches(matches: &clap::ArgMatches) -> Option<ColorChoice> {
+    let mut choice = None;
+    let mut matches = Some(matches);
+    while let Some(current) = matches {
+        if let Ok(Some(&value)) = current.try_get_one::<ColorChoice>("color") {
+            choice = Some(value);
+        }
+        matches = current.subcommand().map(|(_, sub)| sub);
+    }
+    choice
+}
+
+/**
+Create the [`Styles`](https://docs.rs/clap/4/clap/builder/styling/struct.Styles.html)
+of `clap` 4 for the help and error output, according to [`resolve`].
+
+This is a convenience function equivalent to [`clap_styles_with_depth`] with the color depth
+given by [`depth::resolve_depth`]`(None, None)`, where a default choice of [`ColorChoice::Auto`]
+is resolved for the standard output.
+
+```rust
+# use clap4 as clap;
+let cmd = clap::Command::new("prog").styles(should_color::clap_styles());
+```
+*/
+#[cfg(feature = "clap4")]
+pub fn clap_styles() -> clap4::builder::Styles {
+    let (choice, depth) = depth::resolve_depth(None, None);
+    if choice
+        .unwrap_or(ColorChoice::Auto)
+        .for_stream(atty::Stream::Stdout)
+    {
+        clap_styles_with_depth(depth)
+    } else {
+        clap4::builder::Styles::plain()
+    }
+}
+
+/**
+Create the [`Styles`](https://docs.rs/clap/4/clap/builder/styling/struct.Styles.html)
+of `clap` 4 suitable for the given color depth:
+
+- [`ColorDepth::NoColor`](depth::ColorDepth::NoColor): plain styles;
+- [`ColorDepth::Ansi16`](depth::ColorDepth::Ansi16): the default styles of `clap`, with the 16 ANSI colors;
+- [`ColorDepth::Ansi256`](depth::ColorDepth::Ansi256) and [`ColorDepth::TrueColor`](depth::ColorDepth::TrueColor):
+  a richer palette from the 256 colors.
+*/
+#[cfg(feature = "clap4")]
+pub fn clap_styles_with_depth(depth: depth::ColorDepth) -> clap4::builder::Styles {
+    use clap4::builder::styling::{Ansi256Color, AnsiColor, Style, Styles};
+
+    let fg = |n| Style::new().fg_color(Some(Ansi256Color(n).into()));
+    match depth {
+        depth::ColorDepth::NoColor => Styles::plain(),
+        depth::ColorDepth::Ansi16 => Styles::styled(),
+        depth::ColorDepth::Ansi256 | depth::ColorDepth::TrueColor => Styles::styled()
+            .header(fg(214).bold().underline())
+            .usage(fg(214).bold().underline())
+            .literal(fg(81).bold())
+            .placeholder(fg(147))
+            .error(fg(203).bold())
+            .valid(fg(114).bold())
+            .invalid(Style::new().fg_color(Some(AnsiColor::Yellow.into())).bold()),
+    }
+}
+
+/**
+Create the hook builder of [`color-eyre`](https://docs.rs/color-eyre/latest/color_eyre/)
+with the theme suitable for the standard error according to [`ColorChoice::for_stream`]:
+the dark theme if coloring is enabled, and the blank theme otherwise.
+*/
+#[cfg(feature = "eyre")]
+pub fn eyre_hook_builder(choice: ColorChoice) -> color_eyre::config::HookBuilder {
+    use color_eyre::config::{HookBuilder, Theme};
+
+    let theme = if choice.for_stream(atty::Stream::Stderr) {
+        Theme::dark()
+    } else {
+        Theme::new()
+    };
+    HookBuilder::new().theme(theme)
+}
+
+/**
+Install the panic and error report hooks of [`color-eyre`](https://docs.rs/color-eyre/latest/color_eyre/),
+with the theme given by [`eyre_hook_builder`].
+
+The color choice is computed by [`resolve`] with a default of [`ColorChoice::Auto`],
+so that the reports honor `NO_COLOR` and `--color=never`.
+Returns an error if the hooks were already installed.
+
+```no_run
+# let cli_color = None;
+should_color::install_eyre_hook(cli_color).expect("the eyre hook is installed only once");
+```
+*/
+#[cfg(feature = "eyre")]
+pub fn install_eyre_hook(cli: Option<ColorChoice>) -> Result<(), color_eyre::Report> {
+    eyre_hook_builder(resolve(cli).unwrap_or(ColorChoice::Auto)).install()
+}
+
+#[cfg(feature = "colored")]
+impl ColorChoice {
+    /**
+    Configure the [`colored`](https://docs.rs/colored/latest/colored/) crate
+    according to the color setting for a specific stream.
+
+    This sets the global override of `colored` to the result of [`ColorChoice::for_stream`],
+    which is also returned.
+    Notice that `colored` has a single global setting, hence when writing to both
+    the standard output and the standard error this must be called again before switching stream.
+    */
+    pub fn apply_to_colored(&self, stream: atty::Stream) -> bool {
+        let enabled = self.for_stream(stream);
+        ::colored::control::set_override(enabled);
+        enabled
+    }
+}
+
+/**
+Configure the [`colored`](https://docs.rs/colored/latest/colored/) crate for the standard output.
+
+This is a convenience function equivalent to [`resolve`] without an explicit CLI preference
+and a default value of [`ColorChoice::Auto`], followed by [`ColorChoice::apply_to_colored`].
+
+```no_run
+use colored::Colorize;
+
+should_color::apply_colored();
+println!("{}", "green text".green());
+```
+*/
+#[cfg(feature = "colored")]
+pub fn apply_colored() -> bool {
+    resolve(None)
+        .unwrap_or(ColorChoice::Auto)
+        .apply_to_colored(atty::Stream::Stdout)
+}
+
+#[cfg(feature = "yansi")]
+impl ColorChoice {
+    /**
+    Configure the [`yansi`](https://docs.rs/yansi/latest/yansi/) crate
+    according to the color setting for a specific stream.
+
+    This globally enables or disables `yansi` according to the result of [`ColorChoice::for_stream`],
+    which is also returned.
+    */
+    pub fn apply_to_yansi(&self, stream: atty::Stream) -> bool {
+        let enabled = self.for_stream(stream);
+        if enabled {
+            ::yansi::enable();
+        } else {
+            ::yansi::disable();
+        }
+        enabled
+    }
+}
+
+/**
+Configure the [`yansi`](https://docs.rs/yansi/latest/yansi/) crate for the standard output.
+
+This is a convenience function equivalent to [`resolve`] without an explicit CLI preference
+and a default value of [`ColorChoice::Auto`], followed by [`ColorChoice::apply_to_yansi`].
+
+```no_run
+use yansi::Paint;
+
+should_color::apply_yansi();
+println!("{}", "green text".green());
+```
+*/
+#[cfg(feature = "yansi")]
+pub fn apply_yansi() -> bool {
+    resolve(None)
+        .unwrap_or(ColorChoice::Auto)
+        .apply_to_yansi(atty::Stream::Stdout)
+}
+
+/**
+Color settings resolved for the standard output and standard error streams.
+
+This is returned by [`init`], but can also be computed with [`ResolvedStreams::new`].
+*/
+#[cfg(feature = "stream")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResolvedStreams {
+    /// The resolved color choice.
+    pub choice: ColorChoice,
+    /// Whether the standard output should be colorized.
+    pub stdout: bool,
+    /// Whether the standard error should be colorized.
+    pub stderr: bool,
+}
+
+#[cfg(feature = "stream")]
+impl ResolvedStreams {
+    /// Determine the color settings of the standard streams, according to [`ColorChoice::for_stream`].
+    pub fn new(choice: ColorChoice) -> ResolvedStreams {
+        ResolvedStreams {
+            choice,
+            stdout: choice.for_stream(atty::Stream::Stdout),
+            stderr: choice.for_stream(atty::Stream::Stderr),
+        }
+    }
+
+    /**
+    Relate the color settings of the standard output and of the standard error according to `policy`.
+
+    ```
+    use should_color::policy::ConsistencyPolicy;
+    use should_color::{ColorChoice, ResolvedStreams};
+
+    let streams = ResolvedStreams::new(ColorChoice::Auto).with_consistency(ConsistencyPolicy::BothOrNone);
+    assert_eq!(streams.stdout, streams.stderr);
+    ```
+    */
+    pub fn with_consistency(mut self, policy: policy::ConsistencyPolicy) -> ResolvedStreams {
+        (self.stdout, self.stderr) = policy.apply(self.stdout, self.stderr);
+        self
+    }
+}
+
+/**
+Resolve the color choice once and configure all the coloring crates whose features are enabled.
+
+The color choice is computed as [`resolve`]`(cli).unwrap_or(`[`ColorChoice::Auto`]`)`,
+then the following crates are configured:
+
+- [`colored`](https://docs.rs/colored/latest/colored/) (requires the <span class="stab portability"><code>colored</code></span> feature),
+  according to the standard output, see [`ColorChoice::apply_to_colored`];
+- [`yansi`](https://docs.rs/yansi/latest/yansi/) (requires the <span class="stab portability"><code>yansi</code></span> feature),
+  according to the standard output, see [`ColorChoice::apply_to_yansi`];
+- [`anstream`](https://docs.rs/anstream/latest/anstream/), through the global
+  [`colorchoice::ColorChoice`](https://docs.rs/colorchoice/latest/colorchoice/enum.ColorChoice.html)
+  (requires the <span class="stab portability"><code>anstyle</code></span> feature);
+- [`indicatif`](https://docs.rs/indicatif/latest/indicatif/) (requires the <span class="stab portability"><code>indicatif</code></span> feature),
+  according to the standard error, see [`indicatif::apply_indicatif`].
+
+Crates which are configured through builders, such as `env_logger` or `termcolor`,
+can use the returned [`ResolvedStreams`].
+
+```no_run
+# let cli_color = None;
+let streams = should_color::init(cli_color);
+if streams.stderr {
+    eprintln!("\x1b[1mcolorful diagnostics\x1b[0m");
+}
+```
+*/
+#[cfg(feature = "stream")]
+pub fn init(cli: Option<ColorChoice>) -> ResolvedStreams {
+    let choice = resolve(cli).unwrap_or(ColorChoice::Auto);
+
+    #[cfg(feature = "colored")]
+    choice.apply_to_colored(atty::Stream::Stdout);
+
+    #[cfg(feature = "yansi")]
+    choice.apply_to_yansi(atty::Stream::Stdout);
+
+    #[cfg(feature = "anstyle")]
+    colorchoice::ColorChoice::from(choice).write_global();
+
+    #[cfg(feature = "indicatif")]
+    indicatif::apply_indicatif(choice);
+
+    ResolvedStreams::new(choice)
+}
+
+/**
+Scan the command line arguments for a color preference, without fully parsing them.
+
+This is useful when the preference must be known before the argument parser runs,
+for instance to configure the coloring of the help and error messages of the parser itself.
+
+The first item of `args` is assumed to be the program name and is skipped,
+hence `std::env::args_os()` can be passed directly.
+The following arguments are recognized, and the last one wins:
+
+- `--color=WHEN` and `--color WHEN`, where `WHEN` is one of `never`, `auto`, `always`;
+- `--color` not followed by a valid `WHEN`, and `--color=`, equivalent to `--color=always`;
+- `--no-color`, equivalent to `--color=never`.
+
+The scan stops at the first `--` terminator, and arguments which are not valid UTF-8
+or with an invalid `WHEN` are ignored.
+
+```
+# use should_color::{scan_args, ColorChoice};
+let args = ["prog", "--color", "always", "--no-color", "--", "--color=auto"];
+assert_eq!(scan_args(args), Some(ColorChoice::Never));
+```
+
+```
+# #[cfg(feature = "clap")] {
+use should_color::{resolve, scan_args, ColorChoice};
+
+#[derive(clap::Parser)]
+#[clap(color = resolve(scan_args(std::env::args_os())).unwrap_or(ColorChoice::Auto).into())]
+struct Cli {
+    /// Coloring of the output
+    #[clap(long, value_name = "WHEN", arg_enum, global = true)]
+    color: Option<ColorChoice>,
+}
+# }
+```
+*/
+#[cfg(feature = "std")]
+pub fn scan_args<I>(args: I) -> Option<ColorChoice>
+where
+    I: IntoIterator,
+    I::Item: AsRef<std::ffi::OsStr>,
+{
+    let mut args = args.into_iter().skip(1).peekable();
+    let mut choice = None;
+    while let Some(arg) = args.next() {
+        match arg.as_ref().to_str() {
+            Some("--") => break,
+            Some("--no-color") => choice = Some(ColorChoice::Never),
+            Some("--color=") => choice = Some(ColorChoice::Always),
+            Some("--color") => {
+                // the value is consumed only if valid, so that `--color --` still terminates
+                let value = args
+                    .peek()
+                    .and_then(|value| value.as_ref().to_str()?.parse().ok());
+                if value.is_some() {
+                    args.next();
+                }
+                choice = Some(value.unwrap_or(ColorChoice::Always));
+            }
+            Some(arg) => {
+                if let Some(c) = arg.strip_prefix("--color=").and_then(|v| v.parse().ok()) {
+                    choice = Some(c);
+                }
+            }
+            None => {}
+        }
+    }
+    choice
+}
+
+/**
+Get the setting of the `NO_COLOR` environment variable.
+
+The environment variable is treated as follows:
+
+- if not set or `NO_COLOR == ""`: return `None`;
+- if set and `NO_COLOR != ""`: return `Some(`[`ColorChoice::Never`]`)`.
+*/
+#[cfg(all(feature = "no_color", feature = "std"))]
+pub fn no_color() -> Option<ColorChoice> {
+    spec::no_color(env_var(NO_COLOR).as_deref())
+}
+
+/**
+Get the setting of the `CLICOLOR` environment variable.
+
+The environment variable is treated as follows:
+
+- if not set or `CLICOLOR == ""`: return `None`;
+- if set and `CLICOLOR == "0"`: return `Some(`[`ColorChoice::Never`]`)`;
+- if set and `CLICOLOR != ""` and `CLICOLOR != "0"`: return `Some(`[`ColorChoice::Auto`]`)`.
+*/
+#[cfg(all(feature = "clicolor", feature = "std"))]
+pub fn clicolor() -> Option<ColorChoice> {
+    spec::clicolor(env_var(CLICOLOR).as_deref())
+}
+
+/**
+Get the setting of the `CLICOLOR_FORCE` environment variable.
+
+The environment variable is treated as follows:
+
+- if not set or `CLICOLOR_FORCE == ""` or `CLICOLOR_FORCE == "0"`: return `None`;
+- if set and `CLICOLOR_FORCE != ""` and `CLICOLOR_FORCE != "0"`: return `Some`[`ColorChoice::Always`]`)`.
+*/
+#[cfg(all(feature = "clicolor_force", feature = "std"))]
+pub fn clicolor_force() -> Option<ColorChoice> {
+    spec::clicolor_force(env_var(CLICOLOR_FORCE).as_deref())
+}
+
+/**
+Get the setting of the `CARGO_TERM_COLOR` environment variable.
+
+The environment variable is treated as follows:
+
+- if set and `CARGO_TERM_COLOR` is `"never"`, `"auto"`, or `"always"`: return the corresponding [`ColorChoice`];
+- otherwise: return `None`.
+*/
+#[cfg(all(feature = "cargo_term_color", feature = "std"))]
+pub fn cargo_term_color() -> Option<ColorChoice> {
+    spec::cargo_term_color(env_var(CARGO_TERM_COLOR).as_deref())
+}
+
+/// Get the value of an environment variable, replacing invalid unicode with `U+FFFD`.
+#[cfg(feature = "std")]
+pub(crate) fn env_var(name: &str) -> Option<String> {
+    env_var_os(name).map(|s| s.to_string_lossy().into_owned())
+}
+
+/// Get the value of an environment variable as is, for instance when it holds a path.
+#[cfg(feature = "std")]
+pub(crate) fn env_var_os(name: &str) -> Option<std::ffi::OsString> {
+    let value = std::env::var_os(name);
+    trace!("read {name} = {value:?}");
+    value
+}
+
+/**
+Resolve the output color choice from the environment variables and an explicit CLI preference.
+
+Notice that the resolution depends on the activation of the features
+<span class="stab portability"><code>clicolor_force</code></span>,
+<span class="stab portability"><code>clicolor</code></span>,
+<span class="stab portability"><code>no_color</code></span>, and
+<span class="stab portability"><code>terminfo</code></span>.
+Please refer to the [crate level documentation](crate) for a detailed description of the
+resolution process.
+
+Commonly this function will be called as `resolve(cli).unwrap_or(default)` to take into account
+a preference expressed through the CLI arguments and the default behavior of the application.
+See the examples [`colored.rs`] and [`termcolor.rs`] for a demonstration of how to use this function.
+
+[`colored.rs`]: https://github.com/FedericoStra/should-color/blob/master/examples/colored.rs#L36
+[`termcolor.rs`]: https://github.com/FedericoStra/should-color/blob/master/examples/termcolor.rs#L36
+
+# Examples
+
+The following examples assume that all the features
+<span class="stab portability" title="Available on crate feature `clicolor` only"><code>clicolor</code></span>,
 <span class="stab portability" title="Available on crate feature `clicolor_force` only"><code>clicolor_force</code></span>, and
 <span class="stab portability" title="Available on crate feature `no_color` only"><code>no_color</code></span>
 are enabled.
@@ -319,29 +1465,660 @@ are enabled.
   std::env::remove_var("CLICOLOR_FORCE");
   std::env::remove_var("CLICOLOR");
   std::env::remove_var("NO_COLOR");
+  # #[cfg(not(feature = "terminfo"))]
   assert_eq!(resolve(None), None);
   ```
 */
+#[cfg(feature = "std")]
 pub fn resolve(cli: Option<ColorChoice>) -> Option<ColorChoice> {
-    #[cfg(feature = "clicolor_force")]
-    let choice = clicolor_force();
+    Resolver::new().resolve(cli)
+}
 
-    #[cfg(feature = "clicolor_force")]
-    let choice = choice.or(cli);
-    #[cfg(not(feature = "clicolor_force"))]
-    let choice = cli;
+/**
+Resolve the color choice as [`resolve`] does, falling back to `default` if no choice is expressed.
 
-    #[cfg(feature = "clicolor")]
-    let choice = choice.or_else(clicolor);
+This is equivalent to `resolve(cli).unwrap_or(default)`.
+The same resolution is available as the methods [`ColorChoice::resolve_env`]
+and [`ResolveOr::resolve_or`].
 
-    #[cfg(feature = "no_color")]
-    let choice = choice.or_else(no_color);
+```
+# use should_color::{resolve_or, ColorChoice};
+# std::env::remove_var("CLICOLOR_FORCE");
+assert_eq!(resolve_or(Some(ColorChoice::Never), ColorChoice::Auto), ColorChoice::Never);
+```
+*/
+#[cfg(feature = "std")]
+pub fn resolve_or(cli: Option<ColorChoice>, default: ColorChoice) -> ColorChoice {
+    resolve(cli).unwrap_or(default)
+}
 
-    choice
+#[cfg(feature = "std")]
+impl ColorChoice {
+    /**
+    Resolve the color choice from the environment alone, with `self` as the default.
+
+    This is equivalent to [`resolve_or`]`(None, self)`.
+
+    ```
+    # use should_color::ColorChoice;
+    let choice = ColorChoice::Auto.resolve_env();
+    ```
+    */
+    pub fn resolve_env(self) -> ColorChoice {
+        resolve_or(None, self)
+    }
+}
+
+/**
+Resolution of an optional color choice given on the command line, with a default.
+
+```
+use should_color::{ColorChoice, ResolveOr};
+
+# std::env::remove_var("CLICOLOR_FORCE");
+let cli_color = Some(ColorChoice::Never);
+assert_eq!(cli_color.resolve_or(ColorChoice::Auto), ColorChoice::Never);
+```
+*/
+#[cfg(feature = "std")]
+pub trait ResolveOr {
+    /// Resolve the color choice with `self` as the command line choice, as [`resolve_or`] does.
+    fn resolve_or(self, default: ColorChoice) -> ColorChoice;
+}
+
+#[cfg(feature = "std")]
+impl ResolveOr for Option<ColorChoice> {
+    fn resolve_or(self, default: ColorChoice) -> ColorChoice {
+        resolve_or(self, default)
+    }
+}
+
+/**
+Same as [`resolve`], but the environment variables are read from an [`EnvSnapshot`].
+
+The snapshot is captured once and can be used for many resolutions,
+which observe consistent values even if the environment is modified concurrently.
+The resolution is performed by [`Resolver::resolve_from`] with the default resolver,
+hence the overrides (see the [`overrides`] module) are still taken into account.
+
+```
+# use should_color::{resolve_from, ColorChoice, EnvSnapshot};
+let snapshot = EnvSnapshot::from_vars([("CLICOLOR", "0"), ("NO_COLOR", "")]);
+# #[cfg(feature = "clicolor")]
+assert_eq!(resolve_from(&snapshot, None), Some(ColorChoice::Never));
+```
+*/
+#[cfg(feature = "std")]
+pub fn resolve_from(snapshot: &EnvSnapshot, cli: Option<ColorChoice>) -> Option<ColorChoice> {
+    Resolver::default().resolve_from(snapshot, cli)
+}
+
+/**
+Same as [`resolve_from`], but the environment variables are read from an arbitrary mapping,
+such as the captured environment of a remote host or of a container.
+
+The variables which are not consulted by this crate are ignored (see [`EnvSnapshot::from_vars`]).
+
+```
+# use std::collections::HashMap;
+# use std::ffi::OsString;
+# use should_color::{resolve_from_map, ColorChoice};
+let mut env = HashMap::new();
+env.insert(OsString::from("NO_COLOR"), OsString::from("1"));
+# #[cfg(feature = "no_color")]
+assert_eq!(resolve_from_map(&env, None), Some(ColorChoice::Never));
+assert_eq!(resolve_from_map(&env, Some(ColorChoice::Always)), Some(ColorChoice::Always));
+```
+*/
+#[cfg(feature = "std")]
+pub fn resolve_from_map<S: std::hash::BuildHasher>(
+    env: &std::collections::HashMap<std::ffi::OsString, std::ffi::OsString, S>,
+    cli: Option<ColorChoice>,
+) -> Option<ColorChoice> {
+    resolve_from(&EnvSnapshot::from_vars(env), cli)
 }
 
-#[cfg(test)]
+/**
+Resolve the color choice from an ordered sequence of choices, returning the first one which is `Some`.
+
+This gives full control over the order of the sources, without a [`Resolver`].
+The built-in sources are available in the [`sources`] module.
+Notice that the items of an array are all evaluated in advance,
+whereas the items of a lazy iterator are evaluated only until a choice is found.
+
+```
+# use should_color::{resolve_iter, sources, ColorChoice};
+let cli = Some(ColorChoice::Always);
+# #[cfg(all(feature = "no_color", feature = "clicolor"))] {
+std::env::set_var("NO_COLOR", "1");
+let choice = resolve_iter([sources::no_color(), cli, sources::clicolor()]);
+assert_eq!(choice, Some(ColorChoice::Never));
+# }
+
+let lazy: [fn() -> Option<ColorChoice>; 2] = [sources::current_override, || Some(ColorChoice::Auto)];
+assert_eq!(resolve_iter(lazy.iter().map(|source| source())), Some(ColorChoice::Auto));
+```
+*/
+pub fn resolve_iter(choices: impl IntoIterator<Item = Option<ColorChoice>>) -> Option<ColorChoice> {
+    choices.into_iter().flatten().next()
+}
+
+/**
+Independent color choices for the standard output and the standard error.
+
+This is returned by [`resolve_streams`] and [`Resolver::resolve_streams`].
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamChoices {
+    /// The color choice for the standard output.
+    pub stdout: Option<ColorChoice>,
+    /// The color choice for the standard error.
+    pub stderr: Option<ColorChoice>,
+}
+
+/**
+Resolve independent color choices for the standard output and the standard error.
+
+The resolution is the same as [`resolve`], except that each of the environment variables
+`CLICOLOR_FORCE`, `CLICOLOR`, and `NO_COLOR` can be overridden for a single stream
+by appending the suffix `_STDOUT` or `_STDERR` to its name.
+A per-stream variable which is not set or empty is ignored, and the generic one is used instead.
+
+```
+# use should_color::{resolve_streams, ColorChoice};
+# std::env::remove_var("CLICOLOR_FORCE");
+# std::env::remove_var("CLICOLOR");
+# std::env::remove_var("NO_COLOR_STDERR");
+std::env::set_var("NO_COLOR", "1");
+std::env::set_var("CLICOLOR_STDERR", "1");
+let choices = resolve_streams(None);
+assert_eq!(choices.stdout, Some(ColorChoice::Never));
+assert_eq!(choices.stderr, Some(ColorChoice::Auto));
+```
+*/
+#[cfg(feature = "std")]
+pub fn resolve_streams(cli: Option<ColorChoice>) -> StreamChoices {
+    Resolver::new().resolve_streams(cli)
+}
+
+/**
+Resolve independent color choices for the standard output and the standard error,
+as [`resolve_streams`] does, with an explicit CLI preference for each stream.
+
+```
+# use should_color::{resolve_streams_with, ColorChoice, StreamColorChoice};
+# std::env::remove_var("CLICOLOR_FORCE_STDOUT");
+# std::env::remove_var("CLICOLOR_FORCE_STDERR");
+# std::env::remove_var("CLICOLOR_FORCE");
+let cli: StreamColorChoice = "stdout=never,stderr=always".parse()?;
+let choices = resolve_streams_with(cli);
+assert_eq!(choices.stdout, Some(ColorChoice::Never));
+assert_eq!(choices.stderr, Some(ColorChoice::Always));
+# Ok::<(), should_color::ParseColorChoiceError>(())
+```
+*/
+#[cfg(feature = "std")]
+pub fn resolve_streams_with(cli: StreamColorChoice) -> StreamChoices {
+    Resolver::new().resolve_streams_with(cli)
+}
+
+/**
+A color preference given on the command line, possibly different for the standard output
+and the standard error.
+
+It is parsed from either a plain color choice, such as `auto`, which applies to both streams,
+or a comma-separated list of `stdout=WHEN` and `stderr=WHEN`, such as `stdout=never,stderr=always`.
+A plain choice can be mixed with the per-stream ones, and then applies to the streams not listed:
+`never,stderr=auto` disables the colors of the standard output only.
+A stream which is not listed has no preference.
+
+This is useful for tools whose standard output is piped into other programs
+while the standard error stays human-facing.
+The choices are resolved against the environment by [`resolve_streams_with`].
+
+```
+# use should_color::{ColorChoice, StreamColorChoice};
+let choice: StreamColorChoice = "stdout=never,stderr=always".parse()?;
+assert_eq!(choice.stdout, Some(ColorChoice::Never));
+assert_eq!(choice.stderr, Some(ColorChoice::Always));
+
+let choice: StreamColorChoice = "auto".parse()?;
+assert_eq!(choice, StreamColorChoice::from(ColorChoice::Auto));
+
+let choice: StreamColorChoice = "stderr=never".parse()?;
+assert_eq!(choice.stdout, None);
+assert!("stdin=never".parse::<StreamColorChoice>().is_err());
+# Ok::<(), should_color::ParseColorChoiceError>(())
+```
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StreamColorChoice {
+    /// The color preference for the standard output.
+    pub stdout: Option<ColorChoice>,
+    /// The color preference for the standard error.
+    pub stderr: Option<ColorChoice>,
+}
+
+impl From<ColorChoice> for StreamColorChoice {
+    fn from(choice: ColorChoice) -> StreamColorChoice {
+        StreamColorChoice {
+            stdout: Some(choice),
+            stderr: Some(choice),
+        }
+    }
+}
+
+impl From<Option<ColorChoice>> for StreamColorChoice {
+    fn from(choice: Option<ColorChoice>) -> StreamColorChoice {
+        StreamColorChoice {
+            stdout: choice,
+            stderr: choice,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::str::FromStr for StreamColorChoice {
+    type Err = ParseColorChoiceError;
+
+    fn from_str(s: &str) -> Result<StreamColorChoice, ParseColorChoiceError> {
+        let error = || ParseColorChoiceError {
+            value: s.to_owned(),
+        };
+        let mut both = None;
+        let mut choice = StreamColorChoice::default();
+        for item in s.split(',') {
+            let (slot, value) = match item.split_once('=') {
+                Some(("stdout", value)) => (&mut choice.stdout, value),
+                Some(("stderr", value)) => (&mut choice.stderr, value),
+                Some(_) => return Err(error()),
+                None => (&mut both, item),
+            };
+            if slot.is_some() {
+                return Err(error());
+            }
+            *slot = Some(value.parse().map_err(|_| error())?);
+        }
+        choice.stdout = choice.stdout.or(both);
+        choice.stderr = choice.stderr.or(both);
+        Ok(choice)
+    }
+}
+
+/// Choices of the sources with higher and lower priority than the CLI preference, as cached by [`resolve_cached`].
+#[cfg(feature = "std")]
+type CachedChoices = (Option<ColorChoice>, Option<ColorChoice>);
+
+#[cfg(feature = "std")]
+static CACHE: std::sync::RwLock<Option<CachedChoices>> = std::sync::RwLock::new(None);
+
+/**
+Same as [`resolve`], but the environment is read only once and then cached.
+
+This is useful for long-running programs, such as REPLs, TUIs, and daemons,
+which resolve the color choice frequently.
+The cache can be cleared with [`invalidate_cache`], for instance after the application
+knowingly changes the environment variables.
+
+```
+# use should_color::{invalidate_cache, resolve_cached, ColorChoice};
+# std::env::remove_var("CLICOLOR_FORCE");
+# std::env::remove_var("CLICOLOR");
+std::env::set_var("NO_COLOR", "1");
+assert_eq!(resolve_cached(None), Some(ColorChoice::Never));
+assert_eq!(resolve_cached(Some(ColorChoice::Auto)), Some(ColorChoice::Auto));
+
+std::env::remove_var("NO_COLOR");
+assert_eq!(resolve_cached(None), Some(ColorChoice::Never));
+
+invalidate_cache();
+# #[cfg(not(feature = "terminfo"))]
+assert_eq!(resolve_cached(None), None);
+```
+*/
+#[cfg(feature = "std")]
+pub fn resolve_cached(cli: Option<ColorChoice>) -> Option<ColorChoice> {
+    if let Some(choice) = overrides::current_override() {
+        return Some(choice);
+    }
+    let cached = *CACHE.read().unwrap_or_else(PoisonError::into_inner);
+    let (above, below) = match cached {
+        Some(choices) => choices,
+        None => {
+            let choices = (
+                Resolver::with_order([SourceId::ClicolorForce]).resolve(None),
+                Resolver::with_order([
+                    SourceId::CargoTermColor,
+                    SourceId::Clicolor,
+                    SourceId::NoColor,
+                    SourceId::Terminfo,
+                ])
+                .registered_sources()
+                .resolve(None),
+            );
+            *CACHE.write().unwrap_or_else(PoisonError::into_inner) = Some(choices);
+            choices
+        }
+    };
+    above.or(cli).or(below)
+}
+
+/// Clear the cache of [`resolve_cached`], so that the environment is read again by its next call.
+#[cfg(feature = "std")]
+pub fn invalidate_cache() {
+    *CACHE.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
+    #[test]
+    fn test_from_str() {
+        use super::*;
+
+        assert_eq!("never".parse(), Ok(ColorChoice::Never));
+        assert_eq!("auto".parse(), Ok(ColorChoice::Auto));
+        assert_eq!("always".parse(), Ok(ColorChoice::Always));
+        assert!("".parse::<ColorChoice>().is_err());
+        assert_eq!("always-ansi".parse(), Ok(ColorChoice::AlwaysAnsi));
+        assert_eq!("no".parse(), Ok(ColorChoice::Never));
+        assert_eq!("tty".parse(), Ok(ColorChoice::Auto));
+        assert_eq!("if-tty".parse(), Ok(ColorChoice::Auto));
+        assert_eq!("yes".parse(), Ok(ColorChoice::Always));
+        assert_eq!("force".parse(), Ok(ColorChoice::Always));
+
+        for s in ["Auto", "ALWAYS", "0", "1", "true"] {
+            assert!(s.parse::<ColorChoice>().is_err());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "stream")]
+    fn test_resolved_streams() {
+        use super::*;
+
+        let streams = ResolvedStreams::new(ColorChoice::Never);
+        assert!(!streams.stdout && !streams.stderr);
+
+        let streams = ResolvedStreams::new(ColorChoice::Always);
+        assert!(streams.stdout && streams.stderr);
+    }
+
+    #[test]
+    fn test_parse_compiled_default() {
+        use super::*;
+
+        assert_eq!(parse_compiled_default(b""), Some(ColorChoice::Auto));
+        assert_eq!(parse_compiled_default(b"no"), Some(ColorChoice::Never));
+        assert_eq!(
+            parse_compiled_default(b"always-ansi"),
+            Some(ColorChoice::AlwaysAnsi)
+        );
+        assert_eq!(parse_compiled_default(b"sometimes"), None);
+    }
+
+    #[test]
+    fn test_apply_to_command() {
+        use super::*;
+        use std::ffi::OsStr;
+
+        let envs = |choice: ColorChoice| {
+            let mut command = std::process::Command::new("true");
+            choice.apply_to_command(&mut command);
+            let mut envs: Vec<(String, Option<String>)> = command
+                .get_envs()
+                .map(|(k, v)| {
+                    let s = |s: &OsStr| s.to_string_lossy().into_owned();
+                    (s(k), v.map(s))
+                })
+                .collect();
+            envs.sort();
+            envs
+        };
+        let owned = |k: &str, v: Option<&str>| (k.to_owned(), v.map(str::to_owned));
+        assert_eq!(
+            envs(ColorChoice::Never),
+            [owned("CLICOLOR_FORCE", None), owned("NO_COLOR", Some("1"))]
+        );
+        assert_eq!(
+            envs(ColorChoice::Always),
+            [owned("CLICOLOR_FORCE", Some("1")), owned("NO_COLOR", None)]
+        );
+        assert_eq!(
+            envs(ColorChoice::Auto),
+            [owned("CLICOLOR_FORCE", None), owned("NO_COLOR", None)]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "cargo_term_color", feature = "clicolor_force"))]
+    fn test_cargo_term_color() {
+        use super::*;
+
+        let snapshot = EnvSnapshot::from_vars([("CARGO_TERM_COLOR", "never"), ("CLICOLOR", "1")]);
+        assert_eq!(resolve_from(&snapshot, None), Some(ColorChoice::Never));
+        assert_eq!(
+            resolve_from(&snapshot, Some(ColorChoice::Always)),
+            Some(ColorChoice::Always)
+        );
+        let snapshot =
+            EnvSnapshot::from_vars([("CARGO_TERM_COLOR", "never"), ("CLICOLOR_FORCE", "1")]);
+        assert_eq!(resolve_from(&snapshot, None), Some(ColorChoice::Always));
+    }
+
+    #[test]
+    #[cfg(all(feature = "clicolor", feature = "no_color"))]
+    fn test_resolver_resolve_from() {
+        use super::*;
+
+        let snapshot = EnvSnapshot::from_vars([("CLICOLOR", "no"), ("NO_COLOR_STDOUT", "1")]);
+        assert_eq!(resolve_from(&snapshot, None), Some(ColorChoice::Auto));
+        assert_eq!(
+            Resolver::new().lenient(true).resolve_from(&snapshot, None),
+            Some(ColorChoice::Never)
+        );
+        let snapshot = EnvSnapshot::from_vars([("NO_COLOR", ""), ("INSIDE_EMACS", "29.1,comint")]);
+        assert_eq!(resolve_from(&snapshot, None), None);
+        assert_eq!(
+            Resolver::new()
+                .strict_no_color(true)
+                .resolve_from(&snapshot, None),
+            Some(ColorChoice::Never)
+        );
+        assert_eq!(
+            Resolver::with_order([SourceId::Emacs]).resolve_from(&snapshot, None),
+            Some(ColorChoice::Auto)
+        );
+    }
+
+    #[test]
+    fn test_from_git_config_value() {
+        use super::*;
+
+        for s in [
+            "never", "Never", "false", "FALSE", "no", "off", "0", "-0", "",
+        ] {
+            assert_eq!(
+                ColorChoice::from_git_config_value(s),
+                Some(ColorChoice::Never)
+            );
+        }
+        for s in ["auto", "AUTO", "true", "True", "yes", "on", "1", "2", "-1"] {
+            assert_eq!(
+                ColorChoice::from_git_config_value(s),
+                Some(ColorChoice::Auto)
+            );
+        }
+        for s in ["always", "Always"] {
+            assert_eq!(
+                ColorChoice::from_git_config_value(s),
+                Some(ColorChoice::Always)
+            );
+        }
+        for s in ["sometimes", "1.5"] {
+            assert_eq!(ColorChoice::from_git_config_value(s), None);
+        }
+    }
+
+    #[test]
+    fn test_scan_args() {
+        use super::*;
+
+        assert_eq!(scan_args(["prog"]), None);
+        assert_eq!(scan_args(["--color=always"]), None);
+        assert_eq!(
+            scan_args(["prog", "--color=always"]),
+            Some(ColorChoice::Always)
+        );
+        assert_eq!(
+            scan_args(["prog", "--color", "auto"]),
+            Some(ColorChoice::Auto)
+        );
+        assert_eq!(scan_args(["prog", "--no-color"]), Some(ColorChoice::Never));
+        assert_eq!(
+            scan_args(["prog", "--no-color", "x", "--color=always"]),
+            Some(ColorChoice::Always)
+        );
+        assert_eq!(scan_args(["prog", "--", "--color=always"]), None);
+        assert_eq!(
+            scan_args(["prog", "--color", "--", "--no-color"]),
+            Some(ColorChoice::Always)
+        );
+        assert_eq!(scan_args(["prog", "--color"]), Some(ColorChoice::Always));
+        assert_eq!(scan_args(["prog", "--color="]), Some(ColorChoice::Always));
+        assert_eq!(scan_args(["prog", "--color=maybe"]), None);
+        assert_eq!(
+            scan_args(["prog", "--color", "never", "--color=bogus"]),
+            Some(ColorChoice::Never)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "colored")]
+    fn test_apply_to_colored() {
+        use super::*;
+        use ::colored::control::SHOULD_COLORIZE;
+
+        assert!(ColorChoice::Always.apply_to_colored(atty::Stream::Stdout));
+        assert!(SHOULD_COLORIZE.should_colorize());
+
+        assert!(!ColorChoice::Never.apply_to_colored(atty::Stream::Stdout));
+        assert!(!SHOULD_COLORIZE.should_colorize());
+    }
+
+    #[test]
+    #[cfg(feature = "yansi")]
+    fn test_apply_to_yansi() {
+        use super::*;
+
+        assert!(ColorChoice::Always.apply_to_yansi(atty::Stream::Stdout));
+        assert!(::yansi::is_enabled());
+
+        assert!(!ColorChoice::Never.apply_to_yansi(atty::Stream::Stdout));
+        assert!(!::yansi::is_enabled());
+    }
+
+    #[test]
+    #[cfg(feature = "clap")]
+    fn test_color_from_matches() {
+        use super::*;
+
+        let cmd = add_color_arg(clap::Command::new("prog"))
+            .subcommand(clap::Command::new("sub").subcommand(clap::Command::new("inner")));
+        let choice = |args: &[&str]| color_from_matches(&cmd.clone().get_matches_from(args));
+        assert_eq!(choice(&["prog"]), None);
+        assert_eq!(
+            choice(&["prog", "--color=always", "sub"]),
+            Some(ColorChoice::Always)
+        );
+        assert_eq!(
+            choice(&["prog", "sub", "inner", "--color=never"]),
+            Some(ColorChoice::Never)
+        );
+    }
+
+    #[test]
+    fn test_atomic_color_choice() {
+        use super::*;
+        use std::sync::atomic::Ordering;
+
+        let atomic = AtomicColorChoice::default();
+        assert_eq!(atomic.load(Ordering::Relaxed), ColorChoice::Auto);
+        for choice in [
+            ColorChoice::Never,
+            ColorChoice::Auto,
+            ColorChoice::Always,
+            ColorChoice::AlwaysAnsi,
+        ] {
+            atomic.store(choice, Ordering::Relaxed);
+            assert_eq!(atomic.load_option(Ordering::Relaxed), Some(choice));
+        }
+        atomic.store_option(None, Ordering::Relaxed);
+        assert_eq!(atomic.load_option(Ordering::Relaxed), None);
+        assert_eq!(
+            AtomicColorChoice::from(ColorChoice::Never)
+                .swap(ColorChoice::Always, Ordering::Relaxed),
+            ColorChoice::Never
+        );
+    }
+
+    #[test]
+    fn test_stream_color_choice_from_str() {
+        use super::*;
+
+        let parse = |s: &str| s.parse::<StreamColorChoice>().ok();
+        let choice = |stdout, stderr| Some(StreamColorChoice { stdout, stderr });
+        assert_eq!(
+            parse("never"),
+            choice(Some(ColorChoice::Never), Some(ColorChoice::Never))
+        );
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("stdout="), None);
+        assert_eq!(parse("stdout=no"), choice(Some(ColorChoice::Never), None));
+        assert_eq!(
+            parse("stderr=auto,never"),
+            choice(Some(ColorChoice::Never), Some(ColorChoice::Auto))
+        );
+        assert_eq!(parse("stdout=never,stdout=always"), None);
+        assert_eq!(parse("never,auto"), None);
+        assert_eq!(parse("stderr=sometimes"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "clap")]
+    fn test_clap_value_enum() {
+        use super::*;
+        use clap::ValueEnum;
+
+        assert_eq!(
+            ColorChoice::from_str("if-tty", false),
+            Ok(ColorChoice::Auto)
+        );
+        assert_eq!(
+            ColorChoice::from_str("FORCE", true),
+            Ok(ColorChoice::Always)
+        );
+        assert!(ColorChoice::from_str("sometimes", false).is_err());
+        let names: Vec<_> = ColorChoice::value_variants()
+            .iter()
+            .filter_map(|v| v.to_possible_value())
+            .map(|v| v.get_name())
+            .collect();
+        assert_eq!(names, ["never", "auto", "always", "always-ansi"]);
+    }
+
+    #[test]
+    #[cfg(feature = "argh")]
+    fn test_argh() {
+        use super::*;
+        use argh::FromArgValue;
+
+        assert_eq!(
+            ColorChoice::from_arg_value("always"),
+            Ok(ColorChoice::Always)
+        );
+        assert!(ColorChoice::from_arg_value("sometimes").is_err());
+    }
+
     #[test]
     #[cfg(feature = "no_color")]
     fn test_no_color() {
@@ -0,0 +1,415 @@
+/*!
+Lightweight markup for styling text, and conditional printing macros.
+
+The markup consists of tags such as `<red>` or `<bold>`, which open a style,
+and `</>` (or equivalently `</red>`, `</bold>`, etc.), which close the most recently opened one.
+Tags can be nested.
+Text enclosed in `<` and `>` which is not a tag is left untouched.
+
+The supported styles are:
+
+- colors: `black`, `red`, `green`, `yellow`, `blue`, `magenta`, `cyan`, `white`,
+  and their bright variants `bright-black`, `bright-red`, etc.;
+- attributes: `bold`, `dim`, `italic`, `underline`, `blink`, `reverse`, `strike`.
+
+When coloring is enabled the tags are replaced by the corresponding ANSI escape sequences,
+otherwise they are removed.
+
+The macros [`cprint!`](crate::cprint), [`cprintln!`](crate::cprintln), [`ceprint!`](crate::ceprint),
+[`ceprintln!`](crate::ceprintln), [`cwrite!`](crate::cwrite), and [`cwriteln!`](crate::cwriteln)
+apply the markup to the formatted output.
+The markup is interpreted only in the format string: the arguments are inserted verbatim,
+hence a file name or a message which contains `<red>` or `</>` is not restyled.
+Notice that this does not hold for the variables captured implicitly by the format string,
+such as `{name}`, which must be passed explicitly, as in `{name}", name = name`, to be escaped.
+
+```
+use should_color::markup::render;
+
+assert_eq!(render(false, "<bold>error</>: <red>failed</>"), "error: failed");
+assert_eq!(
+    render(true, "<bold>error</>: <red>failed</>"),
+    "\x1b[1merror\x1b[0m: \x1b[31mfailed\x1b[0m"
+);
+```
+*/
+
+use std::fmt;
+
+/// SGR parameter corresponding to a style name.
+fn sgr(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "bold" => "1",
+        "dim" => "2",
+        "italic" => "3",
+        "underline" => "4",
+        "blink" => "5",
+        "reverse" => "7",
+        "strike" => "9",
+        "black" => "30",
+        "red" => "31",
+        "green" => "32",
+        "yellow" => "33",
+        "blue" => "34",
+        "magenta" => "35",
+        "cyan" => "36",
+        "white" => "37",
+        "bright-black" => "90",
+        "bright-red" => "91",
+        "bright-green" => "92",
+        "bright-yellow" => "93",
+        "bright-blue" => "94",
+        "bright-magenta" => "95",
+        "bright-cyan" => "96",
+        "bright-white" => "97",
+        _ => return None,
+    })
+}
+
+/**
+Render the markup in `text`, emitting the ANSI escape sequences only if `enabled` is `true`.
+
+See the [module documentation](self) for a description of the markup.
+*/
+pub fn render(enabled: bool, text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut stack: Vec<&str> = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let tag = rest[1..]
+            .find('>')
+            .map(|end| &rest[1..end + 1])
+            .filter(|tag| match tag.strip_prefix('/') {
+                Some(name) => name.is_empty() || sgr(name).is_some(),
+                None => sgr(tag).is_some(),
+            });
+        match tag {
+            Some(tag) => {
+                rest = &rest[tag.len() + 2..];
+                if tag.starts_with('/') {
+                    if stack.pop().is_some() && enabled {
+                        output.push_str("\x1b[0m");
+                        for code in &stack {
+                            output.push_str(&format!("\x1b[{code}m"));
+                        }
+                    }
+                } else if let Some(code) = sgr(tag) {
+                    stack.push(code);
+                    if enabled {
+                        output.push_str(&format!("\x1b[{code}m"));
+                    }
+                }
+            }
+            None => {
+                output.push('<');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+
+    if enabled && !stack.is_empty() {
+        output.push_str("\x1b[0m");
+    }
+    output
+}
+
+/// Stand-in for `<` in the arguments of the macros, which is a noncharacter reserved for internal use.
+const ESCAPED_LT: char = '\u{FDD0}';
+
+/// Same as [`render`], but the text escaped by [`Escaped`] is inserted verbatim.
+#[doc(hidden)]
+pub fn __render_escaped(enabled: bool, text: &str) -> String {
+    render(enabled, text).replace(ESCAPED_LT, "<")
+}
+
+/**
+Wrapper of an argument of the macros, which escapes the `<` in its output so that it is not taken for a tag.
+
+The formatting options are honored, except when the output contains `<`,
+in which case only the padding and the precision are applied, as for strings.
+*/
+#[doc(hidden)]
+pub struct Escaped<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ?Sized + fmt::Display> fmt::Display for Escaped<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = self.0.to_string();
+        if text.contains('<') {
+            f.pad(&text.replace('<', &ESCAPED_LT.to_string()))
+        } else {
+            self.0.fmt(f)
+        }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Escaped<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = if f.alternate() {
+            format!("{:#?}", self.0)
+        } else {
+            format!("{:?}", self.0)
+        };
+        if text.contains('<') {
+            f.pad(&text.replace('<', &ESCAPED_LT.to_string()))
+        } else {
+            self.0.fmt(f)
+        }
+    }
+}
+
+/// Forward the formatting traits whose output never contains `<`.
+macro_rules! forward {
+    ($($trait:ident),*) => {
+        $(
+            impl<T: ?Sized + fmt::$trait> fmt::$trait for Escaped<'_, T> {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+        )*
+    };
+}
+
+forward!(Binary, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex);
+
+/// Format the arguments of the macros, escaping them, and render the markup of the format string.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cformat {
+    ($enabled:expr; $fmt:literal $(,)?) => {
+        $crate::markup::render($enabled, &::std::format!($fmt))
+    };
+    ($enabled:expr; $fmt:literal, $($args:tt)*) => {
+        $crate::__cformat!(@args $enabled; $fmt; []; $($args)*)
+    };
+    (@args $enabled:expr; $fmt:literal; [$($done:tt)*];) => {
+        $crate::markup::__render_escaped($enabled, &::std::format!($fmt, $($done)*))
+    };
+    (@args $enabled:expr; $fmt:literal; [$($done:tt)*]; $name:ident = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__cformat!(
+            @args $enabled; $fmt;
+            [$($done)* $name = $crate::markup::Escaped(&$value),];
+            $($($rest)*)?
+        )
+    };
+    (@args $enabled:expr; $fmt:literal; [$($done:tt)*]; $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__cformat!(
+            @args $enabled; $fmt;
+            [$($done)* $crate::markup::Escaped(&$value),];
+            $($($rest)*)?
+        )
+    };
+}
+
+/**
+Print to the standard output, rendering the [markup](crate::markup) if coloring is enabled.
+
+The color choice is either given explicitly before a `;`, or computed as
+[`resolve`](crate::resolve)`(None).unwrap_or(`[`ColorChoice::Auto`](crate::ColorChoice::Auto)`)`,
+and it is refined with [`ColorChoice::for_stream`](crate::ColorChoice::for_stream).
+
+```
+use should_color::{cprint, ColorChoice};
+
+cprint!("<green>ok</>\n");
+cprint!(ColorChoice::Never; "<bold>{}</> items\n", 42);
+```
+*/
+#[cfg(feature = "stream")]
+#[macro_export]
+macro_rules! cprint {
+    ($choice:expr; $($arg:tt)*) => {
+        ::std::print!(
+            "{}",
+            $crate::__cformat!(
+                $crate::ColorChoice::for_stream(&$choice, $crate::__atty::Stream::Stdout);
+                $($arg)*
+            )
+        )
+    };
+    ($($arg:tt)*) => {
+        $crate::cprint!(
+            $crate::resolve(None).unwrap_or($crate::ColorChoice::Auto);
+            $($arg)*
+        )
+    };
+}
+
+/**
+Print to the standard output with a newline, rendering the [markup](crate::markup) if coloring is enabled.
+
+See [`cprint!`](crate::cprint) for the determination of the color choice.
+
+```
+use should_color::{cprintln, ColorChoice};
+
+cprintln!("<green>ok</>");
+cprintln!(ColorChoice::Always; "<bold>{}</> items", 42);
+```
+*/
+#[cfg(feature = "stream")]
+#[macro_export]
+macro_rules! cprintln {
+    ($choice:expr; $($arg:tt)*) => {
+        ::std::println!(
+            "{}",
+            $crate::__cformat!(
+                $crate::ColorChoice::for_stream(&$choice, $crate::__atty::Stream::Stdout);
+                $($arg)*
+            )
+        )
+    };
+    ($($arg:tt)*) => {
+        $crate::cprintln!(
+            $crate::resolve(None).unwrap_or($crate::ColorChoice::Auto);
+            $($arg)*
+        )
+    };
+}
+
+/**
+Print to the standard error, rendering the [markup](crate::markup) if coloring is enabled.
+
+See [`cprint!`](crate::cprint) for the determination of the color choice.
+*/
+#[cfg(feature = "stream")]
+#[macro_export]
+macro_rules! ceprint {
+    ($choice:expr; $($arg:tt)*) => {
+        ::std::eprint!(
+            "{}",
+            $crate::__cformat!(
+                $crate::ColorChoice::for_stream(&$choice, $crate::__atty::Stream::Stderr);
+                $($arg)*
+            )
+        )
+    };
+    ($($arg:tt)*) => {
+        $crate::ceprint!(
+            $crate::resolve(None).unwrap_or($crate::ColorChoice::Auto);
+            $($arg)*
+        )
+    };
+}
+
+/**
+Print to the standard error with a newline, rendering the [markup](crate::markup) if coloring is enabled.
+
+See [`cprint!`](crate::cprint) for the determination of the color choice.
+*/
+#[cfg(feature = "stream")]
+#[macro_export]
+macro_rules! ceprintln {
+    ($choice:expr; $($arg:tt)*) => {
+        ::std::eprintln!(
+            "{}",
+            $crate::__cformat!(
+                $crate::ColorChoice::for_stream(&$choice, $crate::__atty::Stream::Stderr);
+                $($arg)*
+            )
+        )
+    };
+    ($($arg:tt)*) => {
+        $crate::ceprintln!(
+            $crate::resolve(None).unwrap_or($crate::ColorChoice::Auto);
+            $($arg)*
+        )
+    };
+}
+
+/**
+Write to a writer, rendering the [markup](crate::markup) if `enabled` is `true`.
+
+Since the stream underlying the writer is not known, coloring is controlled by a `bool`,
+which can be computed for instance with [`ColorChoice::for_stream`](crate::ColorChoice::for_stream).
+
+```
+use std::io::Write;
+use should_color::cwrite;
+
+let mut output = Vec::new();
+cwrite!(output, false; "<red>{}</>", "plain")?;
+assert_eq!(output, b"plain");
+# Ok::<(), std::io::Error>(())
+```
+*/
+#[macro_export]
+macro_rules! cwrite {
+    ($dst:expr, $enabled:expr; $($arg:tt)*) => {
+        ::std::write!(
+            $dst,
+            "{}",
+            $crate::__cformat!($enabled; $($arg)*)
+        )
+    };
+}
+
+/**
+Write to a writer with a newline, rendering the [markup](crate::markup) if `enabled` is `true`.
+
+See [`cwrite!`](crate::cwrite).
+*/
+#[macro_export]
+macro_rules! cwriteln {
+    ($dst:expr, $enabled:expr; $($arg:tt)*) => {
+        ::std::writeln!(
+            $dst,
+            "{}",
+            $crate::__cformat!($enabled; $($arg)*)
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_disabled() {
+        assert_eq!(render(false, "plain"), "plain");
+        assert_eq!(render(false, "<bold><red>a</>b</bold>c"), "abc");
+        assert_eq!(
+            render(false, "a < b > c <unknown> </x>"),
+            "a < b > c <unknown> </x>"
+        );
+        assert_eq!(render(false, "<red>unclosed"), "unclosed");
+        assert_eq!(render(false, "</>extra"), "extra");
+    }
+
+    #[test]
+    fn test_render_enabled() {
+        assert_eq!(
+            render(true, "<bold><red>a</>b</>c"),
+            "\x1b[1m\x1b[31ma\x1b[0m\x1b[1mb\x1b[0mc"
+        );
+        assert_eq!(render(true, "<bright-cyan>x"), "\x1b[96mx\x1b[0m");
+        assert_eq!(render(true, "1 <2> 3"), "1 <2> 3");
+    }
+
+    #[test]
+    fn test_cwrite() {
+        use std::fmt::Write;
+
+        let mut output = String::new();
+        cwriteln!(output, true; "<underline>{}</>", 1).unwrap();
+        cwrite!(output, false; "<underline>{x}</>", x = 2).unwrap();
+        assert_eq!(output, "\x1b[4m1\x1b[0m\n2");
+    }
+
+    #[test]
+    fn test_cwrite_escapes_arguments() {
+        use std::fmt::Write;
+
+        let mut output = String::new();
+        cwrite!(output, true; "<red>{}</> {name:>8}", "a</><bold>b", name = "<x>").unwrap();
+        assert_eq!(output, "\x1b[31ma</><bold>b\x1b[0m      <x>");
+        let mut output = String::new();
+        let n = 7;
+        cwrite!(output, false; "<red>{:03} {:?}</> {n}", 5, "</>").unwrap();
+        assert_eq!(output, "005 \"</>\" 7");
+    }
+}
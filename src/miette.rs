@@ -0,0 +1,66 @@
+/*!
+Interoperability with [`miette`](https://docs.rs/miette/latest/miette/).
+
+The graphical report handler of `miette` decides on its own whether to use colors,
+hence diagnostic reports may disagree with the rest of the output of the program.
+This module configures its theme from the resolved color choice and color depth:
+
+- if coloring is enabled for the standard error, the theme uses 24-bit colors if they are supported,
+  and ANSI colors otherwise;
+- if coloring is disabled, the theme has no styles;
+- the box-drawing characters are Unicode, except on dumb terminals where they are ASCII.
+
+```no_run
+# let cli_color = None;
+should_color::miette::install(cli_color).expect("the miette hook is installed only once");
+```
+*/
+
+use ::miette::{
+    GraphicalReportHandler, GraphicalTheme, InstallError, ThemeCharacters, ThemeStyles,
+};
+
+use crate::depth::{resolve_depth, ColorDepth};
+use crate::ColorChoice;
+
+/// Whether the terminal is able to display the Unicode box-drawing characters.
+fn unicode() -> bool {
+    crate::env_var(crate::depth::TERM).as_deref() != Some("dumb")
+}
+
+/**
+Create the theme of the graphical report handler for the given color choice and color depth,
+according to the color setting of the standard error as determined by [`ColorChoice::for_stream`].
+*/
+pub fn graphical_theme(choice: ColorChoice, depth: ColorDepth) -> GraphicalTheme {
+    let characters = if unicode() {
+        ThemeCharacters::unicode()
+    } else {
+        ThemeCharacters::ascii()
+    };
+    let styles = match depth {
+        _ if !choice.for_stream(atty::Stream::Stderr) => ThemeStyles::none(),
+        ColorDepth::NoColor => ThemeStyles::none(),
+        ColorDepth::TrueColor => ThemeStyles::rgb(),
+        ColorDepth::Ansi16 | ColorDepth::Ansi256 => ThemeStyles::ansi(),
+    };
+    GraphicalTheme { characters, styles }
+}
+
+/// Create a graphical report handler with the theme given by [`graphical_theme`].
+pub fn report_handler(choice: ColorChoice, depth: ColorDepth) -> GraphicalReportHandler {
+    GraphicalReportHandler::new_themed(graphical_theme(choice, depth))
+}
+
+/**
+Install the graphical report handler as the hook of `miette`.
+
+The color choice and the color depth are computed by [`resolve_depth`]`(cli, None)`,
+with a default choice of [`ColorChoice::Auto`].
+Returns an error if the hook was already installed.
+*/
+pub fn install(cli: Option<ColorChoice>) -> Result<(), InstallError> {
+    let (choice, depth) = resolve_depth(cli, None);
+    let choice = choice.unwrap_or(ColorChoice::Auto);
+    ::miette::set_hook(Box::new(move |_| Box::new(report_handler(choice, depth))))
+}
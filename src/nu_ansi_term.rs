@@ -0,0 +1,242 @@
+/*!
+Interoperability with [`nu-ansi-term`](https://docs.rs/nu-ansi-term/latest/nu_ansi_term/),
+the styling crate of [`reedline`](https://docs.rs/reedline/latest/reedline/) and nushell.
+
+A [`Style`](https://docs.rs/nu-ansi-term/latest/nu_ansi_term/struct.Style.html) can be applied to some text
+only when coloring is enabled by means of [`paint`] or [`Styled::new`],
+and APIs which accept a style, such as the highlighters of `reedline`, can be fed with [`gate`].
+
+If the <span class="stab portability"><code>anstyle</code></span> feature is enabled,
+styles can be converted to and from [`anstyle::Style`](https://docs.rs/anstyle/latest/anstyle/struct.Style.html)
+with [`from_anstyle`] and [`to_anstyle`].
+
+```
+use nu_ansi_term::{Color, Style};
+use should_color::nu_ansi_term::Styled;
+
+let error = Style::new().bold().fg(Color::Red);
+
+assert_eq!(Styled::new(false, error, "error").to_string(), "error");
+assert_eq!(
+    Styled::new(true, error, "error").to_string(),
+    "\x1b[1;31merror\x1b[0m"
+);
+```
+*/
+
+use std::fmt;
+
+use ::nu_ansi_term::Style;
+
+use crate::ColorChoice;
+
+/**
+Some text which is displayed with a [`Style`](https://docs.rs/nu-ansi-term/latest/nu_ansi_term/struct.Style.html)
+only if coloring is enabled.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Styled<T> {
+    enabled: bool,
+    style: Style,
+    text: T,
+}
+
+impl<T> Styled<T> {
+    /// Wrap `text` so that it is displayed with `style` if `enabled` is `true`, and plainly otherwise.
+    pub fn new(enabled: bool, style: Style, text: T) -> Styled<T> {
+        Styled {
+            enabled,
+            style,
+            text,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Styled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.enabled {
+            write!(
+                f,
+                "{}{}{}",
+                self.style.prefix(),
+                self.text,
+                self.style.suffix()
+            )
+        } else {
+            self.text.fmt(f)
+        }
+    }
+}
+
+/**
+Display `text` with `style`, but only if coloring is enabled for the standard output.
+
+This is equivalent to
+[`Styled::new`]`(choice.`[`for_stream`](ColorChoice::for_stream)`(atty::Stream::Stdout), style, text)`.
+*/
+pub fn paint<T: fmt::Display>(choice: ColorChoice, style: Style, text: T) -> Styled<T> {
+    Styled::new(choice.for_stream(atty::Stream::Stdout), style, text)
+}
+
+/// Return `style` if `enabled` is `true`, and the plain style otherwise.
+pub fn gate(enabled: bool, style: Style) -> Style {
+    if enabled {
+        style
+    } else {
+        Style::new()
+    }
+}
+
+/// Convert a color of `anstyle` to `nu-ansi-term`.
+#[cfg(feature = "anstyle")]
+fn color_from_anstyle(color: ::anstyle::Color) -> ::nu_ansi_term::Color {
+    use ::anstyle::AnsiColor as A;
+    use ::nu_ansi_term::Color as N;
+
+    match color {
+        ::anstyle::Color::Ansi(color) => match color {
+            A::Black => N::Black,
+            A::Red => N::Red,
+            A::Green => N::Green,
+            A::Yellow => N::Yellow,
+            A::Blue => N::Blue,
+            A::Magenta => N::Magenta,
+            A::Cyan => N::Cyan,
+            A::White => N::White,
+            A::BrightBlack => N::DarkGray,
+            A::BrightRed => N::LightRed,
+            A::BrightGreen => N::LightGreen,
+            A::BrightYellow => N::LightYellow,
+            A::BrightBlue => N::LightBlue,
+            A::BrightMagenta => N::LightMagenta,
+            A::BrightCyan => N::LightCyan,
+            A::BrightWhite => N::LightGray,
+        },
+        ::anstyle::Color::Ansi256(color) => N::Fixed(color.0),
+        ::anstyle::Color::Rgb(color) => N::Rgb(color.0, color.1, color.2),
+    }
+}
+
+/// Convert a color of `nu-ansi-term` to `anstyle`, where the default color has no counterpart.
+#[cfg(feature = "anstyle")]
+fn color_to_anstyle(color: ::nu_ansi_term::Color) -> Option<::anstyle::Color> {
+    use ::anstyle::AnsiColor as A;
+    use ::nu_ansi_term::Color as N;
+
+    let color = match color {
+        N::Black => A::Black,
+        N::Red => A::Red,
+        N::Green => A::Green,
+        N::Yellow => A::Yellow,
+        N::Blue => A::Blue,
+        N::Purple | N::Magenta => A::Magenta,
+        N::Cyan => A::Cyan,
+        N::White => A::White,
+        N::DarkGray => A::BrightBlack,
+        N::LightRed => A::BrightRed,
+        N::LightGreen => A::BrightGreen,
+        N::LightYellow => A::BrightYellow,
+        N::LightBlue => A::BrightBlue,
+        N::LightPurple | N::LightMagenta => A::BrightMagenta,
+        N::LightCyan => A::BrightCyan,
+        N::LightGray => A::BrightWhite,
+        N::Fixed(n) => return Some(::anstyle::Ansi256Color(n).into()),
+        N::Rgb(r, g, b) => return Some(::anstyle::RgbColor(r, g, b).into()),
+        N::Default => return None,
+    };
+    Some(color.into())
+}
+
+/// Accessor of the flag of an effect of a `nu-ansi-term` style.
+#[cfg(feature = "anstyle")]
+type EffectFlag = fn(&mut Style) -> &mut bool;
+
+/// The pairs of corresponding effects of `anstyle` and `nu-ansi-term`.
+#[cfg(feature = "anstyle")]
+const EFFECTS: [(::anstyle::Effects, EffectFlag); 8] = [
+    (::anstyle::Effects::BOLD, |s| &mut s.is_bold),
+    (::anstyle::Effects::DIMMED, |s| &mut s.is_dimmed),
+    (::anstyle::Effects::ITALIC, |s| &mut s.is_italic),
+    (::anstyle::Effects::UNDERLINE, |s| &mut s.is_underline),
+    (::anstyle::Effects::BLINK, |s| &mut s.is_blink),
+    (::anstyle::Effects::INVERT, |s| &mut s.is_reverse),
+    (::anstyle::Effects::HIDDEN, |s| &mut s.is_hidden),
+    (::anstyle::Effects::STRIKETHROUGH, |s| {
+        &mut s.is_strikethrough
+    }),
+];
+
+/**
+Convert an [`anstyle::Style`](https://docs.rs/anstyle/latest/anstyle/struct.Style.html)
+to a `nu-ansi-term` style.
+
+The effects and the underline color which have no counterpart are dropped.
+*/
+#[cfg(feature = "anstyle")]
+pub fn from_anstyle(style: ::anstyle::Style) -> Style {
+    let mut result = Style {
+        foreground: style.get_fg_color().map(color_from_anstyle),
+        background: style.get_bg_color().map(color_from_anstyle),
+        ..Style::new()
+    };
+    for (effect, field) in EFFECTS {
+        *field(&mut result) = style.get_effects().contains(effect);
+    }
+    result
+}
+
+/**
+Convert a `nu-ansi-term` style to an
+[`anstyle::Style`](https://docs.rs/anstyle/latest/anstyle/struct.Style.html).
+
+The default color is treated as no color.
+*/
+#[cfg(feature = "anstyle")]
+pub fn to_anstyle(mut style: Style) -> ::anstyle::Style {
+    let mut effects = ::anstyle::Effects::new();
+    for (effect, field) in EFFECTS {
+        if *field(&mut style) {
+            effects |= effect;
+        }
+    }
+    ::anstyle::Style::new()
+        .fg_color(style.foreground.and_then(color_to_anstyle))
+        .bg_color(style.background.and_then(color_to_anstyle))
+        .effects(effects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint() {
+        let style = Style::new().italic();
+        assert_eq!(paint(ColorChoice::Never, style, 42).to_string(), "42");
+        assert_eq!(
+            paint(ColorChoice::Always, style, 42).to_string(),
+            "\x1b[3m42\x1b[0m"
+        );
+        assert_eq!(gate(false, style), Style::new());
+    }
+
+    #[test]
+    #[cfg(feature = "anstyle")]
+    fn test_anstyle_conversions() {
+        let style = ::anstyle::Style::new()
+            .bold()
+            .underline()
+            .fg_color(Some(::anstyle::AnsiColor::BrightCyan.into()))
+            .bg_color(Some(::anstyle::RgbColor(1, 2, 3).into()));
+        let converted = from_anstyle(style);
+        assert_eq!(
+            converted,
+            Style::new()
+                .bold()
+                .underline()
+                .fg(::nu_ansi_term::Color::LightCyan)
+                .on(::nu_ansi_term::Color::Rgb(1, 2, 3))
+        );
+        assert_eq!(to_anstyle(converted), style);
+    }
+}
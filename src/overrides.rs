@@ -0,0 +1,131 @@
+/*!
+Overrides of the color choice, per thread or for the whole process.
+
+A library embedded in a larger application may need to force or suppress colors
+for a section of its output, without touching the environment.
+The overrides of each thread form a stack, whose top is consulted by [`resolve`](crate::resolve)
+and by every [`Resolver`](crate::Resolver) ahead of all the other sources, but only on that thread.
+
+```
+use should_color::{overrides::OverrideGuard, resolve, ColorChoice};
+
+{
+    let _guard = OverrideGuard::new(ColorChoice::Never);
+    assert_eq!(resolve(Some(ColorChoice::Always)), Some(ColorChoice::Never));
+}
+// the override is popped when the guard is dropped
+```
+
+A global override, set with [`set_global_override`], applies instead to all the threads,
+and is consulted after the override of the current thread.
+*/
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::sync::atomic::Ordering;
+
+use crate::{AtomicColorChoice, ColorChoice};
+
+/// The global override, shared by all the threads.
+static GLOBAL: AtomicColorChoice = AtomicColorChoice::unset();
+
+thread_local! {
+    static STACK: RefCell<Vec<ColorChoice>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Push an override onto the stack of the current thread.
+pub fn push_override(choice: ColorChoice) {
+    STACK.with(|stack| stack.borrow_mut().push(choice));
+}
+
+/// Pop the most recent override from the stack of the current thread, returning it.
+pub fn pop_override() -> Option<ColorChoice> {
+    STACK.with(|stack| stack.borrow_mut().pop())
+}
+
+/**
+Get the most recent override of the current thread, if any,
+and otherwise the global override (see [`global_override`]).
+*/
+pub fn current_override() -> Option<ColorChoice> {
+    STACK
+        .with(|stack| stack.borrow().last().copied())
+        .or_else(global_override)
+}
+
+/**
+Set the global override, which applies to all the threads without an override of their own,
+or clear it with `None`.
+
+The override is read on every resolution, [`resolve_cached`](crate::resolve_cached) included,
+hence a change takes effect immediately and needs no [`invalidate_cache`](crate::invalidate_cache).
+
+For instance, an application can honor a runtime toggle of the colors without recomputing the resolution.
+
+```
+use should_color::overrides::{global_override, set_global_override};
+use should_color::{resolve, resolve_cached, ColorChoice};
+
+set_global_override(Some(ColorChoice::Never));
+let choice = std::thread::spawn(|| resolve(Some(ColorChoice::Always))).join().unwrap();
+assert_eq!(choice, Some(ColorChoice::Never));
+set_global_override(Some(ColorChoice::Always));
+assert_eq!(resolve_cached(None), Some(ColorChoice::Always));
+set_global_override(None);
+assert_eq!(global_override(), None);
+```
+*/
+pub fn set_global_override(choice: Option<ColorChoice>) {
+    GLOBAL.store_option(choice, Ordering::Relaxed);
+}
+
+/// Get the global override, if any.
+pub fn global_override() -> Option<ColorChoice> {
+    GLOBAL.load_option(Ordering::Relaxed)
+}
+
+/**
+Guard which pushes an override when created and pops it when dropped.
+
+The guard cannot be sent to another thread, since the override belongs to the current one.
+*/
+#[derive(Debug)]
+#[must_use = "the override is popped when the guard is dropped"]
+pub struct OverrideGuard {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl OverrideGuard {
+    /// Push an override onto the stack of the current thread, until the guard is dropped.
+    pub fn new(choice: ColorChoice) -> OverrideGuard {
+        push_override(choice);
+        OverrideGuard {
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for OverrideGuard {
+    fn drop(&mut self) {
+        pop_override();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack() {
+        assert_eq!(current_override(), None);
+        push_override(ColorChoice::Never);
+        {
+            let _guard = OverrideGuard::new(ColorChoice::Always);
+            assert_eq!(current_override(), Some(ColorChoice::Always));
+            assert_eq!(crate::resolve(None), Some(ColorChoice::Always));
+        }
+        assert_eq!(current_override(), Some(ColorChoice::Never));
+        assert_eq!(pop_override(), Some(ColorChoice::Never));
+        assert_eq!(pop_override(), None);
+    }
+}
@@ -0,0 +1,129 @@
+/*!
+Detection of pagers which can display ANSI colors.
+
+When the output of a program is piped into a pager, it is not a TTY,
+but colors should still be emitted if the pager is able to display them,
+as is the case for `less -R`.
+
+The pager is determined from the `PAGER` environment variable, defaulting to `less`,
+and the options of `less` are read from its command line and from the `LESS` environment variable.
+
+```
+use should_color::pager::supports_color;
+
+assert!(supports_color("less -R", None));
+assert!(supports_color("/usr/bin/less", Some("FRX")));
+assert!(!supports_color("less", None));
+assert!(!supports_color("more", None));
+```
+*/
+
+#[cfg(feature = "stream")]
+use crate::ColorChoice;
+
+/// Name of the `PAGER` environment variable.
+pub const PAGER: &str = "PAGER";
+/// Name of the `LESS` environment variable.
+pub const LESS: &str = "LESS";
+
+/// Whether an option of `less` enables the display of raw control characters.
+fn is_less_option_raw(option: &str) -> bool {
+    option == "--RAW-CONTROL-CHARS"
+        || option == "--raw-control-chars"
+        || (!option.starts_with("--") && option.contains(['R', 'r']))
+}
+
+/**
+Determine whether the pager `command` can display ANSI colors.
+
+The command is split on whitespace and its program is identified by the file name:
+
+- `less` supports colors if invoked with `-R`, `-r`, or `--RAW-CONTROL-CHARS`
+  (possibly combined with other flags, as in `-FRX`),
+  or if the `less_env` options (the value of `LESS`) contain them;
+- `bat`, `cat`, `delta`, `moar`, `moor`, `most`, and `ov` always support colors;
+- other pagers are assumed not to support colors.
+*/
+pub fn supports_color(command: &str, less_env: Option<&str>) -> bool {
+    let mut words = command.split_whitespace();
+    let program = match words.next() {
+        Some(program) => program,
+        None => return false,
+    };
+    let name = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    let name = name.strip_suffix(".exe").unwrap_or(name);
+    match name {
+        "less" => {
+            words.any(|word| word.starts_with('-') && is_less_option_raw(word))
+                // the value of `LESS` may omit the leading dash
+                || less_env.is_some_and(|options| options.split_whitespace().any(is_less_option_raw))
+        }
+        "bat" | "cat" | "delta" | "moar" | "moor" | "most" | "ov" => true,
+        _ => false,
+    }
+}
+
+/// Get the pager command from the `PAGER` environment variable, defaulting to `less`.
+pub fn pager() -> String {
+    match crate::env_var(PAGER) {
+        Some(pager) if !pager.trim().is_empty() => pager,
+        _ => "less".to_owned(),
+    }
+}
+
+#[cfg(feature = "stream")]
+impl ColorChoice {
+    /**
+    Determine the color setting for output which is piped into the pager given by [`pager`].
+
+    If the choice is [`ColorChoice::Never`] or [`ColorChoice::Always`] (or [`ColorChoice::AlwaysAnsi`]),
+    the result will be `false` and `true` respectively.
+
+    If the choice is [`ColorChoice::Auto`], then the answer depends on whether
+    the standard output, where the pager will display the text, is a TTY
+    and the pager can display colors according to [`supports_color`].
+    */
+    pub fn for_pager(&self) -> bool {
+        self.for_pager_command(&pager())
+    }
+
+    /// Same as [`ColorChoice::for_pager`], but for an explicit pager `command`.
+    pub fn for_pager_command(&self, command: &str) -> bool {
+        match self {
+            ColorChoice::Never => false,
+            ColorChoice::Always | ColorChoice::AlwaysAnsi => true,
+            ColorChoice::Auto => {
+                crate::is_tty(atty::Stream::Stdout)
+                    && supports_color(command, crate::env_var(LESS).as_deref())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports_color() {
+        assert!(!supports_color("", None));
+        assert!(!supports_color("less", Some("FX")));
+        assert!(supports_color("less -R", None));
+        assert!(supports_color("less -FRX", None));
+        assert!(supports_color("less --RAW-CONTROL-CHARS", None));
+        assert!(!supports_color("less --quit-if-one-screen", None));
+        assert!(!supports_color("less README", None));
+        assert!(supports_color("less", Some("-R")));
+        assert!(supports_color("less", Some("FRX")));
+        assert!(supports_color("C:\\bin\\less.exe -R", None));
+        assert!(supports_color("bat --plain", None));
+        assert!(!supports_color("more", Some("R")));
+    }
+
+    #[test]
+    #[cfg(feature = "stream")]
+    fn test_for_pager_command() {
+        assert!(!ColorChoice::Never.for_pager_command("less -R"));
+        assert!(ColorChoice::Always.for_pager_command("more"));
+    }
+}
@@ -0,0 +1,218 @@
+/*!
+Policies which refine the meaning of the color choices.
+
+By default [`ColorChoice::Auto`] enables colors only if the stream is a TTY.
+An [`AutoPolicy`] allows applications to express more nuanced defaults without abandoning the `Auto` variant:
+it is consulted by [`ColorChoice::for_stream`], either globally with [`AutoPolicy::set_global`]
+or for a single decision with [`ColorChoice::for_stream_with_policy`].
+
+```
+use should_color::policy::AutoPolicy;
+use should_color::ColorChoice;
+
+AutoPolicy::TtyOrAnsiConsumer.set_global();
+let colorize = ColorChoice::Auto.for_stream(atty::Stream::Stdout);
+# AutoPolicy::Tty.set_global();
+```
+
+A [`ConsistencyPolicy`] instead relates the decisions for the standard output and the standard error,
+which may differ when only one of them is piped.
+*/
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::ColorChoice;
+
+/// The meaning of [`ColorChoice::Auto`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum AutoPolicy {
+    /// Enable colors only if the stream is a TTY.
+    #[default]
+    Tty,
+    /**
+    Enable colors if the stream is a TTY, or if the output is known to be consumed by a program
+    which renders ANSI colors even though it is not a TTY:
+
+    - the logs of a continuous integration service which renders colors
+      (see [`ci::supports_ansi`](crate::ci::supports_ansi));
+    - the console of an integrated development environment (see [`terminal::ide`](crate::terminal::ide));
+    - the pager of git, which sets `GIT_PAGER_IN_USE`, if it can display colors
+      (see [`pager::supports_color`](crate::pager::supports_color)).
+    */
+    TtyOrAnsiConsumer,
+    /**
+    Enable colors unless the stream is redirected to a regular file,
+    hence also when it is piped into another program.
+    */
+    NotFile,
+}
+
+/// The global policy, as the discriminant of [`AutoPolicy`].
+static GLOBAL: AtomicU8 = AtomicU8::new(AutoPolicy::Tty as u8);
+
+impl AutoPolicy {
+    /// Get the global policy, consulted by [`ColorChoice::for_stream`] ([`AutoPolicy::Tty`] by default).
+    pub fn global() -> AutoPolicy {
+        match GLOBAL.load(Ordering::Relaxed) {
+            1 => AutoPolicy::TtyOrAnsiConsumer,
+            2 => AutoPolicy::NotFile,
+            _ => AutoPolicy::Tty,
+        }
+    }
+
+    /// Set the global policy, consulted by [`ColorChoice::for_stream`].
+    pub fn set_global(self) {
+        GLOBAL.store(self as u8, Ordering::Relaxed);
+    }
+
+    /// Determine whether [`ColorChoice::Auto`] enables colors for `stream` according to the policy.
+    pub fn enables(&self, stream: atty::Stream) -> bool {
+        match self {
+            AutoPolicy::Tty => crate::is_tty(stream),
+            AutoPolicy::TtyOrAnsiConsumer => {
+                crate::is_tty(stream)
+                    || crate::ci::supports_ansi()
+                    || crate::terminal::ide().is_some()
+                    || git_pager_supports_color()
+            }
+            AutoPolicy::NotFile => crate::is_tty(stream) || is_file(stream) == Some(false),
+        }
+    }
+}
+
+/// Whether the output is piped by git into its pager, and the pager can display colors.
+fn git_pager_supports_color() -> bool {
+    crate::env_var("GIT_PAGER_IN_USE").is_some_and(|value| !value.is_empty() && value != "false")
+        && crate::pager::supports_color(
+            &crate::pager::pager(),
+            crate::env_var(crate::pager::LESS).as_deref(),
+        )
+}
+
+/// Whether `stream` is redirected to a regular file, or `None` if it cannot be determined.
+fn is_file(stream: atty::Stream) -> Option<bool> {
+    #[cfg(unix)]
+    let handle = {
+        use std::os::fd::AsFd;
+        match stream {
+            atty::Stream::Stdout => std::io::stdout().as_fd().try_clone_to_owned(),
+            atty::Stream::Stderr => std::io::stderr().as_fd().try_clone_to_owned(),
+            atty::Stream::Stdin => std::io::stdin().as_fd().try_clone_to_owned(),
+        }
+    };
+    #[cfg(windows)]
+    let handle = {
+        use std::os::windows::io::AsHandle;
+        match stream {
+            atty::Stream::Stdout => std::io::stdout().as_handle().try_clone_to_owned(),
+            atty::Stream::Stderr => std::io::stderr().as_handle().try_clone_to_owned(),
+            atty::Stream::Stdin => std::io::stdin().as_handle().try_clone_to_owned(),
+        }
+    };
+    #[cfg(any(unix, windows))]
+    return std::fs::File::from(handle.ok()?)
+        .metadata()
+        .ok()
+        .map(|metadata| metadata.is_file());
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = stream;
+        None
+    }
+}
+
+/**
+The relation between the color settings of the standard output and of the standard error.
+
+When the standard output is piped but the standard error is a TTY, [`ColorChoice::Auto`]
+colorizes only the latter, and a terminal which shows both streams interleaves plain and colored output.
+Applications can opt into a uniform behavior with [`ResolvedStreams::with_consistency`](crate::ResolvedStreams::with_consistency).
+
+```
+use should_color::policy::ConsistencyPolicy;
+
+assert_eq!(ConsistencyPolicy::Independent.apply(false, true), (false, true));
+assert_eq!(ConsistencyPolicy::BothOrNone.apply(false, true), (false, false));
+assert_eq!(ConsistencyPolicy::FollowStderr.apply(false, true), (true, true));
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ConsistencyPolicy {
+    /// Decide for each stream on its own.
+    #[default]
+    Independent,
+    /// Colorize both streams only if both would be colorized, and none otherwise.
+    BothOrNone,
+    /// Colorize both streams if and only if the standard output would be colorized.
+    FollowStdout,
+    /// Colorize both streams if and only if the standard error would be colorized.
+    FollowStderr,
+}
+
+impl ConsistencyPolicy {
+    /// Apply the policy to the color settings of the standard output and of the standard error.
+    pub fn apply(&self, stdout: bool, stderr: bool) -> (bool, bool) {
+        match self {
+            ConsistencyPolicy::Independent => (stdout, stderr),
+            ConsistencyPolicy::BothOrNone => (stdout && stderr, stdout && stderr),
+            ConsistencyPolicy::FollowStdout => (stdout, stdout),
+            ConsistencyPolicy::FollowStderr => (stderr, stderr),
+        }
+    }
+}
+
+impl ColorChoice {
+    /**
+    Same as [`ColorChoice::for_stream`], but [`ColorChoice::Auto`] is resolved according to `policy`
+    instead of the global policy.
+    */
+    pub fn for_stream_with_policy(&self, stream: atty::Stream, policy: AutoPolicy) -> bool {
+        match self {
+            ColorChoice::Never => false,
+            ColorChoice::Always | ColorChoice::AlwaysAnsi => true,
+            #[cfg(feature = "colorless_term")]
+            ColorChoice::Auto if crate::termdb::is_colorless() => false,
+            #[cfg(all(windows, feature = "windows"))]
+            ColorChoice::Auto => policy.enables(stream) && crate::windows::supports_ansi(),
+            #[cfg(not(all(windows, feature = "windows")))]
+            ColorChoice::Auto => policy.enables(stream),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_stream_with_policy() {
+        for policy in [
+            AutoPolicy::Tty,
+            AutoPolicy::TtyOrAnsiConsumer,
+            AutoPolicy::NotFile,
+        ] {
+            assert!(!ColorChoice::Never.for_stream_with_policy(atty::Stream::Stdout, policy));
+            assert!(ColorChoice::Always.for_stream_with_policy(atty::Stream::Stdout, policy));
+        }
+    }
+
+    #[test]
+    fn test_consistency_policy() {
+        for (stdout, stderr) in [(false, false), (false, true), (true, false), (true, true)] {
+            assert_eq!(
+                ConsistencyPolicy::Independent.apply(stdout, stderr),
+                (stdout, stderr)
+            );
+            let (out, err) = ConsistencyPolicy::BothOrNone.apply(stdout, stderr);
+            assert!(out == err && out == (stdout && stderr));
+            assert_eq!(
+                ConsistencyPolicy::FollowStdout.apply(stdout, stderr),
+                (stdout, stdout)
+            );
+            assert_eq!(
+                ConsistencyPolicy::FollowStderr.apply(stdout, stderr),
+                (stderr, stderr)
+            );
+        }
+    }
+}
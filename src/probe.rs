@@ -0,0 +1,127 @@
+/*!
+Interrogation of the terminal for its color capabilities.
+
+The environment variables may be missing or wrong, for instance across `sudo`, `ssh`, or containers,
+but the terminal itself can be asked what it supports:
+
+- the Primary Device Attributes query (DA1, `ESC [ c`) is answered by virtually every terminal,
+  and the attribute `22` announces ANSI colors;
+- a DECRQSS query for the graphic rendition (`ESC P $ q m ESC \`), sent after selecting a 24-bit color,
+  is answered with the same color only by terminals which support 24-bit colors.
+
+The interrogation is never performed unless explicitly requested, either with [`probe`]
+or with [`DepthResolver::probe`](crate::depth::DepthResolver::probe).
+It writes to and reads from the controlling terminal, hence it should not be performed
+while other threads are using it.
+Interrogation is currently supported only on Unix.
+*/
+
+use std::time::Duration;
+
+use crate::depth::ColorDepth;
+
+/// Select a 24-bit background color, request the graphic rendition, reset it, and request DA1.
+const QUERY: &[u8] = b"\x1b[48:2:1:2:3m\x1bP$qm\x1b\\\x1b[0m\x1b[c";
+
+/// The capabilities reported by the terminal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Probe {
+    /// The attributes of the Primary Device Attributes reply, such as `[62, 22]`.
+    pub attributes: Vec<u16>,
+    /// Whether the terminal confirmed the support of 24-bit colors.
+    pub truecolor: bool,
+}
+
+impl Probe {
+    /// Whether the terminal announced ANSI colors with the attribute `22`.
+    pub fn ansi_color(&self) -> bool {
+        self.attributes.contains(&22)
+    }
+
+    /**
+    The color depth confirmed by the terminal.
+
+    Returns [`ColorDepth::TrueColor`] if 24-bit colors are confirmed,
+    [`ColorDepth::Ansi16`] if ANSI colors are announced, and `None` otherwise,
+    since many terminals support colors without announcing them.
+    */
+    pub fn depth(&self) -> Option<ColorDepth> {
+        if self.truecolor {
+            Some(ColorDepth::TrueColor)
+        } else if self.ansi_color() {
+            Some(ColorDepth::Ansi16)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether `reply` ends with a complete DA1 reply.
+fn is_da1_complete(reply: &[u8]) -> bool {
+    reply.ends_with(b"c") && find(reply, b"\x1b[?").is_some()
+}
+
+/// Find the position of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Parse the replies to [`QUERY`].
+fn parse_reply(reply: &[u8]) -> Option<Probe> {
+    let da1 = &reply[find(reply, b"\x1b[?")? + 3..];
+    let da1 = &da1[..da1.iter().position(|&b| b == b'c')?];
+    let attributes = std::str::from_utf8(da1)
+        .ok()?
+        .split(';')
+        .map(|attribute| attribute.parse::<u16>().ok())
+        .collect::<Option<_>>()?;
+    let truecolor = find(reply, b"\x1bP1$r").is_some_and(|start| {
+        let sgr = &reply[start + 5..];
+        find(sgr, b"48:2:1:2:3").is_some() || find(sgr, b"48;2;1;2;3").is_some()
+    });
+    Some(Probe {
+        attributes,
+        truecolor,
+    })
+}
+
+/**
+Interrogate the terminal for its color capabilities.
+
+The query is performed only if `stream` is a TTY,
+and the reply is awaited at most for `timeout` (100 milliseconds are usually sufficient).
+Returns `None` if the terminal does not reply in time or the reply cannot be parsed.
+*/
+pub fn probe(stream: atty::Stream, timeout: Duration) -> Option<Probe> {
+    if !crate::is_tty(stream) {
+        return None;
+    }
+    let reply = crate::query::query_terminal(QUERY, timeout, is_da1_complete)?;
+    parse_reply(&reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(
+            parse_reply(b"\x1bP1$r0;48:2:1:2:3m\x1b\\\x1b[?64;1;2;6;9;15;16;17;18;21;22;28c"),
+            Some(Probe {
+                attributes: vec![64, 1, 2, 6, 9, 15, 16, 17, 18, 21, 22, 28],
+                truecolor: true,
+            })
+        );
+        let probe = parse_reply(b"\x1bP0$r\x1b\\\x1b[?62;22c").unwrap();
+        assert!(!probe.truecolor);
+        assert_eq!(probe.depth(), Some(ColorDepth::Ansi16));
+        let probe = parse_reply(b"\x1b[?65;1;9c").unwrap();
+        assert_eq!(probe.depth(), None);
+        assert_eq!(parse_reply(b"\x1b[?6"), None);
+        assert!(is_da1_complete(b"\x1b[?1;2c"));
+        assert!(!is_da1_complete(b"\x1bP1$r0;48:2:1:2:3m"));
+    }
+}
@@ -0,0 +1,61 @@
+/*!
+Python bindings of the resolution, through [PyO3](https://pyo3.rs).
+
+The extension module `should_color` exposes [`ColorChoice`] with the method `for_stream`,
+and the function `resolve`, so that Python programs share the same semantics as Rust consumers:
+
+```python
+from should_color import ColorChoice, resolve
+
+choice = resolve(None) or ColorChoice.Auto
+if choice.for_stream("stderr"):
+    ...
+```
+
+The module can be built and installed with [maturin](https://www.maturin.rs)
+by running `maturin develop` or `maturin build` in the root of the repository.
+*/
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::ColorChoice;
+
+#[pymethods]
+impl ColorChoice {
+    /**
+    Determine the color setting for the stream `"stdout"` or `"stderr"`.
+
+    See [`ColorChoice::for_stream`].
+    */
+    #[pyo3(name = "for_stream")]
+    fn py_for_stream(&self, stream: &str) -> PyResult<bool> {
+        let stream = match stream {
+            "stdout" => atty::Stream::Stdout,
+            "stderr" => atty::Stream::Stderr,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "invalid stream {stream:?} (expected one of \"stdout\", \"stderr\")"
+                )))
+            }
+        };
+        Ok(self.for_stream(stream))
+    }
+}
+
+/// Resolve the output color choice from the environment variables and an explicit CLI preference.
+///
+/// See [`resolve`](crate::resolve).
+#[pyfunction]
+#[pyo3(name = "resolve", signature = (cli = None))]
+fn py_resolve(cli: Option<ColorChoice>) -> Option<ColorChoice> {
+    crate::resolve(cli)
+}
+
+/// The `should_color` extension module.
+#[pymodule]
+fn should_color(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<ColorChoice>()?;
+    m.add_function(wrap_pyfunction!(py_resolve, m)?)?;
+    Ok(())
+}
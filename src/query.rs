@@ -0,0 +1,84 @@
+//! Interrogation of the controlling terminal with escape sequences.
+
+use std::time::Duration;
+
+/**
+Send `query` to the controlling terminal and read the reply, until `is_complete` returns `true`
+or the `timeout` expires.
+
+The terminal is temporarily put in non-canonical mode without echo, and restored afterwards.
+Returns `None` if there is no controlling terminal or it does not reply in time.
+*/
+#[cfg(unix)]
+pub(crate) fn query_terminal(
+    query: &[u8],
+    timeout: Duration,
+    is_complete: impl Fn(&[u8]) -> bool,
+) -> Option<Vec<u8>> {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let fd = tty.as_raw_fd();
+
+    // SAFETY: `termios` is a plain C struct, which is initialized by `tcgetattr`.
+    let mut original: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+        return None;
+    }
+    let mut raw = original;
+    raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+    raw.c_cc[libc::VMIN] = 0;
+    raw.c_cc[libc::VTIME] = 0;
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+        return None;
+    }
+
+    let mut reply = Vec::new();
+    let complete = (|| {
+        tty.write_all(query).ok()?;
+        tty.flush().ok()?;
+        let deadline = Instant::now() + timeout;
+        let mut buf = [0; 64];
+        while !is_complete(&reply) {
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+            let mut pollfd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let millis = remaining.as_millis().clamp(1, libc::c_int::MAX as u128) as libc::c_int;
+            if unsafe { libc::poll(&mut pollfd, 1, millis) } <= 0 {
+                return None;
+            }
+            let n = tty.read(&mut buf).ok()?;
+            reply.extend_from_slice(&buf[..n]);
+        }
+        Some(())
+    })();
+
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+    complete.map(|()| reply)
+}
+
+/// Interrogation of the terminal is supported only on Unix.
+#[cfg(not(unix))]
+pub(crate) fn query_terminal(
+    _query: &[u8],
+    _timeout: Duration,
+    _is_complete: impl Fn(&[u8]) -> bool,
+) -> Option<Vec<u8>> {
+    None
+}
+
+/// Whether `reply` contains an operating system command terminated by `BEL` or `ESC \`.
+#[cfg(feature = "query")]
+pub(crate) fn is_osc_complete(reply: &[u8]) -> bool {
+    reply.ends_with(b"\x07") || reply.ends_with(b"\x1b\\")
+}
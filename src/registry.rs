@@ -0,0 +1,129 @@
+/*!
+Registration of sources by any crate of the application, without a central registration point.
+
+Large applications are composed of many crates, and each component may want to contribute
+a color preference, such as a plugin configuration or a workspace setting.
+A source registered with [`register_source!`](crate::register_source) is collected at link time by
+[`inventory`](https://docs.rs/inventory/latest/inventory/) and picked up automatically by
+[`Resolver::new`](crate::Resolver::new), hence by [`resolve`](crate::resolve),
+with lower priority than all the built-in sources.
+
+```
+use should_color::{register_source, ColorChoice};
+
+fn workspace_color() -> Option<ColorChoice> {
+    // read the workspace settings
+    None
+}
+
+register_source!("workspace", workspace_color);
+register_source!("plugin", 10, || None);
+
+let names: Vec<_> = should_color::registry::sources().map(|source| source.name()).collect();
+assert_eq!(names, ["plugin", "workspace"]);
+```
+*/
+
+use crate::resolver::Source;
+use crate::ColorChoice;
+
+#[doc(hidden)]
+pub use ::inventory as __inventory;
+
+/**
+A source registered with [`register_source!`](crate::register_source).
+
+See the [module documentation](self) for an example.
+*/
+#[derive(Debug)]
+pub struct RegisteredSource {
+    name: &'static str,
+    priority: i32,
+    get: fn() -> Option<ColorChoice>,
+}
+
+impl RegisteredSource {
+    /**
+    Create a source named `name` which expresses the choice returned by `get`.
+
+    Among the registered sources, the ones with higher `priority` are consulted first.
+    */
+    pub const fn new(
+        name: &'static str,
+        priority: i32,
+        get: fn() -> Option<ColorChoice>,
+    ) -> RegisteredSource {
+        RegisteredSource {
+            name,
+            priority,
+            get,
+        }
+    }
+
+    /// Get the name of the source, which identifies it in diagnostics.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the priority of the source among the registered sources.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Get the function which computes the choice of the source.
+    pub fn getter(&self) -> fn() -> Option<ColorChoice> {
+        self.get
+    }
+}
+
+impl Source for RegisteredSource {
+    fn get(&self) -> Option<ColorChoice> {
+        (self.get)()
+    }
+}
+
+::inventory::collect!(RegisteredSource);
+
+/**
+Register a source, which is picked up by [`Resolver::new`](crate::Resolver::new).
+
+The macro is invoked at the top level of a module, as
+`register_source!(name, get)` or `register_source!(name, priority, get)`,
+where `get` is a function or a non-capturing closure returning `Option<ColorChoice>`
+and the priority defaults to `0` (see [`RegisteredSource::new`]).
+*/
+#[macro_export]
+macro_rules! register_source {
+    ($name:expr, $get:expr $(,)?) => {
+        $crate::register_source!($name, 0, $get);
+    };
+    ($name:expr, $priority:expr, $get:expr $(,)?) => {
+        $crate::registry::__inventory::submit! {
+            $crate::registry::RegisteredSource::new($name, $priority, $get)
+        }
+    };
+}
+
+/// Iterate over the registered sources, by decreasing priority and then by name.
+pub fn sources() -> impl Iterator<Item = &'static RegisteredSource> {
+    let mut sources: Vec<_> = ::inventory::iter::<RegisteredSource>.into_iter().collect();
+    sources.sort_by_key(|source| (core::cmp::Reverse(source.priority), source.name));
+    sources.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::register_source!("test_registry_low", -1, || None);
+    crate::register_source!("test_registry_high", 1, || None);
+
+    #[test]
+    fn test_sources_order() {
+        let names: Vec<_> = sources()
+            .map(RegisteredSource::name)
+            .filter(|name| name.starts_with("test_registry"))
+            .collect();
+        assert_eq!(names, ["test_registry_high", "test_registry_low"]);
+    }
+}
@@ -0,0 +1,162 @@
+/*!
+Diagnostics of the environment variables which affect the resolution.
+
+When users report that colors are wrong on their machine, applications can print an [`EnvReport`],
+for instance under a `--verbose` flag, to show the raw values of all the variables consulted by this crate.
+
+```
+use should_color::report::EnvReport;
+
+let report = EnvReport::capture();
+eprintln!("{report}");
+```
+*/
+
+use std::fmt;
+
+/// Names of the environment variables consulted by this crate.
+pub const VARIABLES: &[&str] = &[
+    "CLICOLOR_FORCE",
+    "CLICOLOR_FORCE_STDOUT",
+    "CLICOLOR_FORCE_STDERR",
+    "CLICOLOR",
+    "CLICOLOR_STDOUT",
+    "CLICOLOR_STDERR",
+    "NO_COLOR",
+    "NO_COLOR_STDOUT",
+    "NO_COLOR_STDERR",
+    "CARGO_TERM_COLOR",
+    "FORCE_COLOR",
+    "TERM",
+    "ConEmuANSI",
+    "ANSICON",
+    "COLORTERM",
+    "TERM_PROGRAM",
+    "VTE_VERSION",
+    "KITTY_WINDOW_ID",
+    "WEZTERM_EXECUTABLE",
+    "WT_SESSION",
+    "KONSOLE_VERSION",
+    "DOMTERM",
+    "TERMINAL_EMULATOR",
+    "VSCODE_PID",
+    "PYCHARM_HOSTED",
+    "CI",
+    "GITHUB_ACTIONS",
+    "GITLAB_CI",
+    "BUILDKITE",
+    "CIRCLECI",
+    "TRAVIS",
+    "TF_BUILD",
+    "APPVEYOR",
+    "BITBUCKET_BUILD_NUMBER",
+    "TEAMCITY_VERSION",
+    "DRONE",
+    "JENKINS_URL",
+    "INSIDE_EMACS",
+    "TMUX",
+    "STY",
+    "SSH_TTY",
+    "SSH_CONNECTION",
+    "SSH_CLIENT",
+    "FORCE_HYPERLINK",
+    "COLORFGBG",
+    "PAGER",
+    "GIT_PAGER_IN_USE",
+    "LESS",
+    "RUST_LOG_STYLE",
+    "FORCE_UNICODE",
+    "NO_STYLE",
+    "ACCESSIBLE",
+    "LC_ALL",
+    "LC_CTYPE",
+    "LANG",
+];
+
+/**
+Snapshot of the raw values of the environment variables listed in [`VARIABLES`].
+
+The [`Display`](fmt::Display) implementation produces a table with one variable per line,
+where the values are quoted and unset variables are shown as `<unset>`,
+so that empty values can be told apart.
+*/
+#[derive(Clone, PartialEq, Eq)]
+pub struct EnvReport {
+    vars: Vec<(&'static str, Option<String>)>,
+}
+
+impl EnvReport {
+    /// Capture the current values of the environment variables.
+    pub fn capture() -> EnvReport {
+        EnvReport::capture_with(crate::env_var)
+    }
+
+    /// Capture the values of the variables from a lookup function.
+    fn capture_with(var: impl Fn(&str) -> Option<String>) -> EnvReport {
+        EnvReport {
+            vars: VARIABLES.iter().map(|&name| (name, var(name))).collect(),
+        }
+    }
+
+    /// Get the captured value of the variable `name`, or `None` if it was unset or is not consulted.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars
+            .iter()
+            .find(|(n, _)| *n == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Iterate over the names of the variables and their captured values, in the order of [`VARIABLES`].
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, Option<&str>)> + '_ {
+        self.vars
+            .iter()
+            .map(|(name, value)| (*name, value.as_deref()))
+    }
+}
+
+impl fmt::Debug for EnvReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl fmt::Display for EnvReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = VARIABLES.iter().map(|name| name.len()).max().unwrap_or(0);
+        for (name, value) in self.iter() {
+            match value {
+                Some(value) => writeln!(f, "{name:width$}  {value:?}")?,
+                None => writeln!(f, "{name:width$}  <unset>")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let report = EnvReport::capture_with(|name| match name {
+            "NO_COLOR" => Some("1".to_owned()),
+            "CLICOLOR" => Some(String::new()),
+            _ => None,
+        });
+        assert_eq!(report.get("NO_COLOR"), Some("1"));
+        assert_eq!(report.get("CLICOLOR"), Some(""));
+        assert_eq!(report.get("TERM"), None);
+
+        let table = report.to_string();
+        assert_eq!(table.lines().count(), VARIABLES.len());
+        let has_line = |expected: [&str; 2]| {
+            table
+                .lines()
+                .any(|line| line.split_whitespace().eq(expected))
+        };
+        assert!(has_line(["NO_COLOR", "\"1\""]));
+        assert!(has_line(["CLICOLOR", "\"\""]));
+        assert!(has_line(["TERM", "<unset>"]));
+    }
+}
@@ -0,0 +1,629 @@
+/*!
+Configurable resolution of the color choice from an ordered list of sources.
+*/
+
+#![cfg_attr(
+    not(any(feature = "clicolor_force", feature = "clicolor", feature = "no_color")),
+    allow(dead_code, unused_variables)
+)]
+
+use std::fmt;
+
+use crate::{ColorChoice, EnvSnapshot, StreamChoices, StreamColorChoice};
+
+/**
+A source of color preference, such as an environment variable or a configuration file.
+
+Every function `fn() -> Option<ColorChoice>`, such as [`no_color`](crate::no_color),
+[`clicolor`](crate::clicolor), and [`clicolor_force`](crate::clicolor_force), is a source.
+*/
+pub trait Source {
+    /// Get the color choice expressed by this source, or `None` if it expresses no preference.
+    fn get(&self) -> Option<ColorChoice>;
+}
+
+impl<F: Fn() -> Option<ColorChoice>> Source for F {
+    fn get(&self) -> Option<ColorChoice> {
+        self()
+    }
+}
+
+/**
+A source which reads a custom environment variable and interprets its value with a parser.
+
+The parser is not called if the variable is not set, and values which are not valid unicode
+are converted lossily.
+
+```
+use should_color::{ColorChoice, EnvVarSource, Resolver};
+
+let source = EnvVarSource::new("MY_APP_COLOR", |value| value.parse().ok());
+let resolver = Resolver::new().source(source);
+# std::env::remove_var("CLICOLOR_FORCE");
+# std::env::remove_var("CLICOLOR");
+# std::env::remove_var("NO_COLOR");
+std::env::set_var("MY_APP_COLOR", "never");
+# #[cfg(not(feature = "terminfo"))]
+assert_eq!(resolver.resolve(None), Some(ColorChoice::Never));
+```
+*/
+pub struct EnvVarSource<F> {
+    name: String,
+    parse: F,
+}
+
+impl<F: Fn(&str) -> Option<ColorChoice>> EnvVarSource<F> {
+    /// Create a source which reads the variable `name` and interprets its value with `parse`.
+    pub fn new(name: impl Into<String>, parse: F) -> EnvVarSource<F> {
+        EnvVarSource {
+            name: name.into(),
+            parse,
+        }
+    }
+
+    /// Get the name of the variable.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<F> fmt::Debug for EnvVarSource<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnvVarSource")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Fn(&str) -> Option<ColorChoice>> Source for EnvVarSource<F> {
+    fn get(&self) -> Option<ColorChoice> {
+        crate::env_var(&self.name).and_then(|value| (self.parse)(&value))
+    }
+}
+
+/**
+Identifier of a built-in source, used to configure the priority order of a [`Resolver`].
+
+The sources which depend on a disabled feature never express a preference.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SourceId {
+    /// The explicit CLI preference passed to [`Resolver::resolve`].
+    Cli,
+    /// The `CLICOLOR_FORCE` environment variable (see [`clicolor_force`](crate::clicolor_force)).
+    ClicolorForce,
+    /// The `CARGO_TERM_COLOR` environment variable (see [`cargo_term_color`](crate::cargo_term_color)).
+    CargoTermColor,
+    /// The `CLICOLOR` environment variable (see [`clicolor`](crate::clicolor)).
+    Clicolor,
+    /// The `NO_COLOR` environment variable (see [`no_color`](crate::no_color)).
+    NoColor,
+    /**
+    The Emacs buffer hosting the program (see [`terminal::emacs_color`](crate::terminal::emacs_color)).
+
+    It is not part of [`SourceId::DEFAULT_ORDER`]: applications opt in by including it in the order.
+    */
+    Emacs,
+    /**
+    The terminfo database (see [`terminfo::terminfo`](crate::terminfo::terminfo)),
+    falling back on the bundled table (see [`termdb::termdb`](crate::termdb::termdb))
+    if the database has no entry for the terminal.
+
+    The Emacs buffers which render ANSI colors despite `TERM=dumb` are not considered colorless
+    (see [`termdb::is_colorless`](crate::termdb::is_colorless)).
+    */
+    Terminfo,
+    /**
+    The `FORCE_COLOR` environment variable (see [`depth::force_color`](crate::depth::force_color)).
+
+    It is not part of [`SourceId::DEFAULT_ORDER`], but it is taken into account by
+    [`depth::resolve_depth`](crate::depth::resolve_depth) with the same priority as `CLICOLOR_FORCE`.
+    */
+    ForceColor,
+    /**
+    The accessibility settings (see [`accessibility::accessibility`](crate::accessibility::accessibility)).
+
+    It is not part of [`SourceId::DEFAULT_ORDER`]: applications opt in by including it in the order.
+    */
+    Accessibility,
+}
+
+impl SourceId {
+    /// The priority order of [`resolve`](crate::resolve), from higher to lower.
+    pub const DEFAULT_ORDER: [SourceId; 6] = [
+        SourceId::ClicolorForce,
+        SourceId::Cli,
+        SourceId::CargoTermColor,
+        SourceId::Clicolor,
+        SourceId::NoColor,
+        SourceId::Terminfo,
+    ];
+
+    /**
+    Get the color choice expressed by the source.
+
+    The environment variables are read through `env`,
+    and they are looked up with the given `suffix` first (see [`var`]).
+    */
+    fn get(
+        self,
+        cli: Option<ColorChoice>,
+        resolver: &Resolver,
+        suffix: &str,
+        env: Env<'_>,
+    ) -> Option<ColorChoice> {
+        let lenient = resolver.lenient;
+        let var = |name, suffix| var(env, name, suffix);
+        match self {
+            SourceId::Cli => cli,
+            #[cfg(feature = "clicolor_force")]
+            SourceId::ClicolorForce => match var(crate::CLICOLOR_FORCE, suffix) {
+                Some(s) if lenient && is_falsy(&s) => None,
+                s => crate::spec::clicolor_force(s.as_deref()),
+            },
+            #[cfg(feature = "cargo_term_color")]
+            SourceId::CargoTermColor => {
+                crate::spec::cargo_term_color(env(crate::CARGO_TERM_COLOR).as_deref())
+            }
+            #[cfg(feature = "clicolor")]
+            SourceId::Clicolor => match var(crate::CLICOLOR, suffix) {
+                Some(s) if lenient && is_falsy(&s) => Some(ColorChoice::Never),
+                s => crate::spec::clicolor(s.as_deref()),
+            },
+            #[cfg(feature = "no_color")]
+            SourceId::NoColor => match var(crate::NO_COLOR, suffix) {
+                Some(s) if lenient && is_falsy(&s) => None,
+                s if resolver.strict_no_color => crate::spec::no_color_strict(s.as_deref()),
+                s => crate::spec::no_color(s.as_deref()),
+            },
+            SourceId::Emacs => crate::terminal::emacs_with(env)
+                .filter(crate::terminal::Emacs::renders_ansi)
+                .map(|_| ColorChoice::Auto),
+            #[cfg(feature = "terminfo")]
+            SourceId::Terminfo => {
+                crate::termdb::is_colorless_with(env).then_some(ColorChoice::Never)
+            }
+            SourceId::ForceColor => var(crate::depth::FORCE_COLOR, suffix)
+                .and_then(|s| crate::depth::parse_force_color(&s))
+                .map(|(choice, _)| choice),
+            SourceId::Accessibility => crate::accessibility::accessibility_with(env),
+            #[cfg(not(feature = "clicolor_force"))]
+            SourceId::ClicolorForce => None,
+            #[cfg(not(feature = "cargo_term_color"))]
+            SourceId::CargoTermColor => None,
+            #[cfg(not(feature = "clicolor"))]
+            SourceId::Clicolor => None,
+            #[cfg(not(feature = "no_color"))]
+            SourceId::NoColor => None,
+            #[cfg(not(feature = "terminfo"))]
+            SourceId::Terminfo => None,
+        }
+    }
+}
+
+/// An entry of the resolution order.
+enum Entry {
+    /// A built-in source.
+    Builtin(SourceId),
+    /// Any other source.
+    Source(Box<dyn Source>),
+}
+
+/// Lookup of the environment variables, either in the environment of the process or in an [`EnvSnapshot`].
+type Env<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// Get the environment variable `name + suffix` if set and not empty, or else `name`.
+fn var(env: Env<'_>, name: &str, suffix: &str) -> Option<String> {
+    if !suffix.is_empty() {
+        match env(&format!("{name}{suffix}")) {
+            Some(s) if !s.is_empty() => return Some(s),
+            _ => {}
+        }
+    }
+    env(name)
+}
+
+/// Whether the value is one of `0`, `false`, `no`, `off` (ignoring ASCII case).
+fn is_falsy(value: &str) -> bool {
+    ["0", "false", "no", "off"]
+        .iter()
+        .any(|f| value.eq_ignore_ascii_case(f))
+}
+
+/**
+Resolver of the color choice from an ordered list of sources.
+
+The resolution returns the choice of the first source, in order of priority from higher to lower,
+which expresses a preference.
+[`Resolver::new`] creates a resolver with the same sources and order as [`resolve`](crate::resolve),
+[`Resolver::with_order`] creates a resolver with the built-in sources in a custom order,
+and further sources can be appended with lower priority by [`Resolver::source`].
+
+```
+use should_color::{ColorChoice, Resolver};
+
+let resolver = Resolver::new().source(|| Some(ColorChoice::Never));
+# std::env::remove_var("CLICOLOR_FORCE");
+assert_eq!(resolver.resolve(Some(ColorChoice::Always)), Some(ColorChoice::Always));
+# std::env::remove_var("CLICOLOR");
+# std::env::remove_var("NO_COLOR");
+# #[cfg(not(feature = "terminfo"))]
+assert_eq!(resolver.resolve(None), Some(ColorChoice::Never));
+```
+*/
+pub struct Resolver {
+    entries: Vec<Entry>,
+    lenient: bool,
+    clicolor_default: bool,
+    promote_in_ide: bool,
+    promote_in_ci: bool,
+    strict_no_color: bool,
+}
+
+impl fmt::Debug for Resolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resolver")
+            .field("sources", &self.entries.len())
+            .field("lenient", &self.lenient)
+            .field("clicolor_default", &self.clicolor_default)
+            .field("promote_in_ide", &self.promote_in_ide)
+            .field("promote_in_ci", &self.promote_in_ci)
+            .field("strict_no_color", &self.strict_no_color)
+            .finish()
+    }
+}
+
+impl Default for Resolver {
+    fn default() -> Resolver {
+        Resolver::new()
+    }
+}
+
+impl Resolver {
+    /**
+    Create a resolver with the same sources and order as [`resolve`](crate::resolve).
+
+    The sources depend on the activation of the features
+    <span class="stab portability"><code>clicolor_force</code></span>,
+    <span class="stab portability"><code>clicolor</code></span>,
+    <span class="stab portability"><code>no_color</code></span>, and
+    <span class="stab portability"><code>terminfo</code></span>.
+    With the <span class="stab portability"><code>inventory</code></span> feature,
+    the registered sources follow the built-in ones (see [`Resolver::registered_sources`]).
+    */
+    pub fn new() -> Resolver {
+        Resolver::with_order(SourceId::DEFAULT_ORDER).registered_sources()
+    }
+
+    /**
+    Create a resolver with the built-in sources in the given order of priority, from higher to lower.
+
+    For instance, an application can let `NO_COLOR` override even an explicit `--color=always`:
+
+    ```
+    use should_color::{ColorChoice, Resolver, SourceId};
+
+    let resolver = Resolver::with_order([
+        SourceId::NoColor,
+        SourceId::ClicolorForce,
+        SourceId::Cli,
+        SourceId::Clicolor,
+    ]);
+    std::env::set_var("NO_COLOR", "1");
+    assert_eq!(resolver.resolve(Some(ColorChoice::Always)), Some(ColorChoice::Never));
+    ```
+    */
+    pub fn with_order(order: impl IntoIterator<Item = SourceId>) -> Resolver {
+        Resolver {
+            entries: order.into_iter().map(Entry::Builtin).collect(),
+            lenient: false,
+            clicolor_default: false,
+            promote_in_ide: false,
+            promote_in_ci: false,
+            strict_no_color: false,
+        }
+    }
+
+    /// Append a source, with lower priority than all the sources already present.
+    pub fn source(mut self, source: impl Source + 'static) -> Resolver {
+        self.entries.push(Entry::Source(Box::new(source)));
+        self
+    }
+
+    /**
+    Append the sources registered with [`register_source!`](crate::register_source),
+    with lower priority than all the sources already present.
+
+    The registered sources are ordered by decreasing priority, and then by name.
+    Without the <span class="stab portability"><code>inventory</code></span> feature no source is appended.
+    */
+    pub fn registered_sources(self) -> Resolver {
+        #[cfg(feature = "inventory")]
+        return crate::registry::sources()
+            .fold(self, |resolver, source| resolver.source(source.getter()));
+        #[cfg(not(feature = "inventory"))]
+        self
+    }
+
+    /**
+    Whether to interpret the environment variables leniently (disabled by default).
+
+    Users often write `NO_COLOR=false` or `CLICOLOR=no` expecting it to disable the variable
+    or the colors respectively, but according to the specifications any non-empty value
+    of `NO_COLOR` disables colors and any value of `CLICOLOR` other than `0` enables them.
+    In lenient mode the values `false`, `no`, `off` (ignoring ASCII case), and `0` are falsy:
+
+    - a falsy `CLICOLOR_FORCE` or `NO_COLOR` is treated as unset;
+    - a falsy `CLICOLOR` is treated as `CLICOLOR=0`.
+
+    Truthy values such as `true`, `yes`, and `on` are already interpreted as intended.
+    The mode does not affect the sources appended with [`Resolver::source`].
+
+    ```
+    use should_color::{ColorChoice, Resolver};
+
+    # std::env::remove_var("CLICOLOR_FORCE");
+    # std::env::remove_var("NO_COLOR");
+    std::env::set_var("CLICOLOR", "no");
+    assert_eq!(Resolver::new().resolve(None), Some(ColorChoice::Auto));
+    assert_eq!(Resolver::new().lenient(true).resolve(None), Some(ColorChoice::Never));
+    ```
+    */
+    pub fn lenient(mut self, lenient: bool) -> Resolver {
+        self.lenient = lenient;
+        self
+    }
+
+    /**
+    Whether a set but empty `NO_COLOR` disables colors (disabled by default).
+
+    The [NO_COLOR FAQ](https://no-color.org) states that the presence of the variable,
+    regardless of its value, should disable colors, while this crate traditionally ignores `NO_COLOR=""`
+    (see [`no_color`](crate::no_color)).
+    In strict mode any value of `NO_COLOR` yields `Some(`[`ColorChoice::Never`]`)`
+    (see [`spec::no_color_strict`](crate::spec::no_color_strict)).
+    The per-stream variables such as `NO_COLOR_STDOUT` are still ignored when empty.
+
+    ```
+    use should_color::{ColorChoice, Resolver};
+
+    # std::env::remove_var("CLICOLOR_FORCE");
+    # std::env::remove_var("CLICOLOR");
+    std::env::set_var("NO_COLOR", "");
+    assert_eq!(Resolver::new().strict_no_color(true).resolve(None), Some(ColorChoice::Never));
+    ```
+    */
+    #[cfg(feature = "no_color")]
+    pub fn strict_no_color(mut self, strict: bool) -> Resolver {
+        self.strict_no_color = strict;
+        self
+    }
+
+    /**
+    Whether an unset `CLICOLOR` means `CLICOLOR=1` (disabled by default).
+
+    The [updated proposal](https://bixense.com/clicolors/) recommends colorized output by default
+    when the output is a tty, with `CLICOLOR=0` opting out.
+    In this mode, if `CLICOLOR` is unset or empty and no source expresses a preference,
+    the resolution returns `Some(`[`ColorChoice::Auto`]`)` instead of `None`.
+    Notice that `NO_COLOR` and all the other sources still take precedence over the implied default.
+
+    ```
+    use should_color::{ColorChoice, Resolver};
+
+    # std::env::remove_var("CLICOLOR_FORCE");
+    std::env::remove_var("CLICOLOR");
+    std::env::remove_var("NO_COLOR");
+    # #[cfg(not(feature = "terminfo"))]
+    assert_eq!(Resolver::new().clicolor_default(true).resolve(None), Some(ColorChoice::Auto));
+
+    std::env::set_var("NO_COLOR", "1");
+    assert_eq!(Resolver::new().clicolor_default(true).resolve(None), Some(ColorChoice::Never));
+    ```
+    */
+    #[cfg(feature = "clicolor")]
+    pub fn clicolor_default(mut self, clicolor_default: bool) -> Resolver {
+        self.clicolor_default = clicolor_default;
+        self
+    }
+
+    /**
+    Whether to promote [`ColorChoice::Auto`] to [`ColorChoice::Always`] inside an
+    integrated development environment (disabled by default).
+
+    The consoles which run programs in VS Code and in the JetBrains IDEs are not ttys,
+    but they render ANSI colors, hence [`ColorChoice::for_stream`](crate::ColorChoice::for_stream)
+    would disable colors there.
+    In this mode, if the resolution returns `Some(`[`ColorChoice::Auto`]`)`
+    and [`terminal::ide`](crate::terminal::ide) detects an IDE, it returns `Some(`[`ColorChoice::Always`]`)` instead.
+
+    ```
+    use should_color::{ColorChoice, Resolver};
+
+    # std::env::remove_var("CLICOLOR_FORCE");
+    # std::env::remove_var("NO_COLOR");
+    # std::env::remove_var("VSCODE_PID");
+    std::env::set_var("TERM_PROGRAM", "vscode");
+    let resolver = Resolver::new().promote_in_ide(true);
+    assert_eq!(resolver.resolve(Some(ColorChoice::Auto)), Some(ColorChoice::Always));
+    assert_eq!(resolver.resolve(Some(ColorChoice::Never)), Some(ColorChoice::Never));
+    ```
+    */
+    pub fn promote_in_ide(mut self, promote: bool) -> Resolver {
+        self.promote_in_ide = promote;
+        self
+    }
+
+    /**
+    Whether to promote [`ColorChoice::Auto`] to [`ColorChoice::Always`] inside a
+    continuous integration service whose logs render ANSI colors (disabled by default).
+
+    The logs of CI jobs are not ttys, but services such as GitHub Actions and GitLab render colors,
+    while others, such as Jenkins without the AnsiColor plugin, do not
+    (see [`CiProvider::supports_ansi`](crate::ci::CiProvider::supports_ansi)).
+    In this mode, if the resolution returns `Some(`[`ColorChoice::Auto`]`)`
+    and [`ci::supports_ansi`](crate::ci::supports_ansi) holds, it returns `Some(`[`ColorChoice::Always`]`)` instead.
+
+    ```
+    use should_color::{ColorChoice, Resolver};
+
+    # std::env::remove_var("CLICOLOR_FORCE");
+    # std::env::remove_var("NO_COLOR");
+    std::env::set_var("GITHUB_ACTIONS", "true");
+    let resolver = Resolver::new().promote_in_ci(true);
+    assert_eq!(resolver.resolve(Some(ColorChoice::Auto)), Some(ColorChoice::Always));
+    ```
+    */
+    pub fn promote_in_ci(mut self, promote: bool) -> Resolver {
+        self.promote_in_ci = promote;
+        self
+    }
+
+    /**
+    Resolve the output color choice from the sources and an explicit CLI preference.
+
+    Commonly this method will be called as `resolver.resolve(cli).unwrap_or(default)`,
+    as for [`resolve`](crate::resolve).
+    */
+    pub fn resolve(&self, cli: Option<ColorChoice>) -> Option<ColorChoice> {
+        self.resolve_with_suffix(cli, "")
+    }
+
+    /**
+    Resolve independent color choices for the standard output and the standard error.
+
+    The environment variables read by the built-in sources can be overridden for a single stream,
+    as described in [`resolve_streams`](crate::resolve_streams).
+    The sources appended with [`Resolver::source`] are shared by both streams.
+    */
+    pub fn resolve_streams(&self, cli: Option<ColorChoice>) -> StreamChoices {
+        self.resolve_streams_with(cli.into())
+    }
+
+    /**
+    Same as [`Resolver::resolve_streams`], but with an explicit CLI preference for each stream
+    (see [`StreamColorChoice`]).
+    */
+    pub fn resolve_streams_with(&self, cli: StreamColorChoice) -> StreamChoices {
+        StreamChoices {
+            stdout: self.resolve_with_suffix(cli.stdout, "_STDOUT"),
+            stderr: self.resolve_with_suffix(cli.stderr, "_STDERR"),
+        }
+    }
+
+    /**
+    Same as [`Resolver::resolve`], but the environment variables are read from an [`EnvSnapshot`].
+
+    The built-in sources read the snapshot, while the sources appended with [`Resolver::source`]
+    and the registered ones are consulted as usual.
+    */
+    pub fn resolve_from(
+        &self,
+        snapshot: &EnvSnapshot,
+        cli: Option<ColorChoice>,
+    ) -> Option<ColorChoice> {
+        self.resolve_in(cli, "", &|name| snapshot.var(name))
+    }
+
+    /// Resolve the color choice, looking up the environment variables with the given `suffix` first.
+    fn resolve_with_suffix(&self, cli: Option<ColorChoice>, suffix: &str) -> Option<ColorChoice> {
+        self.resolve_in(cli, suffix, &crate::env_var)
+    }
+
+    /// Resolve the color choice, reading the environment variables through `env`.
+    fn resolve_in(
+        &self,
+        cli: Option<ColorChoice>,
+        suffix: &str,
+        env: Env<'_>,
+    ) -> Option<ColorChoice> {
+        if let Some(choice) = crate::overrides::current_override() {
+            trace!("thread-local override {choice:?}");
+            return Some(choice);
+        }
+        let choice = self.resolve_sources(cli, suffix, env);
+        match choice {
+            Some(ColorChoice::Auto)
+                if self.promote_in_ide && crate::terminal::ide_with(env).is_some() =>
+            {
+                trace!("promoted Auto inside an IDE");
+                Some(ColorChoice::Always)
+            }
+            Some(ColorChoice::Auto)
+                if self.promote_in_ci
+                    && crate::ci::provider_with(env).is_some_and(|ci| ci.supports_ansi()) =>
+            {
+                trace!("promoted Auto inside a CI service");
+                Some(ColorChoice::Always)
+            }
+            _ => choice,
+        }
+    }
+
+    /// Resolve the color choice from the sources, without the promotion of [`ColorChoice::Auto`].
+    fn resolve_sources(
+        &self,
+        cli: Option<ColorChoice>,
+        suffix: &str,
+        env: Env<'_>,
+    ) -> Option<ColorChoice> {
+        let choice = self.entries.iter().find_map(|entry| match entry {
+            Entry::Builtin(id) => {
+                let choice = id.get(cli, self, suffix, env);
+                trace!("source {id:?} expresses {choice:?}");
+                choice
+            }
+            Entry::Source(source) => {
+                let choice = source.get();
+                trace!("custom source expresses {choice:?}");
+                choice
+            }
+        });
+        if choice.is_some() {
+            trace!("resolved {choice:?}");
+            return choice;
+        }
+        let choice = self.implied_clicolor(suffix, env);
+        trace!("no source expresses a preference, resolved {choice:?}");
+        choice
+    }
+
+    /// The choice implied by an unset `CLICOLOR` if [`Resolver::clicolor_default`] is enabled.
+    fn implied_clicolor(&self, suffix: &str, env: Env<'_>) -> Option<ColorChoice> {
+        #[cfg(feature = "clicolor")]
+        if self.clicolor_default && var(env, crate::CLICOLOR, suffix).is_none_or(|s| s.is_empty()) {
+            return Some(ColorChoice::Auto);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_var_source() {
+        const NAME: &str = "SHOULD_COLOR_TEST_ENV_VAR_SOURCE";
+        let source =
+            EnvVarSource::new(NAME, |value| (value == "off").then_some(ColorChoice::Never));
+
+        std::env::remove_var(NAME);
+        assert_eq!(source.get(), None);
+        std::env::set_var(NAME, "on");
+        assert_eq!(source.get(), None);
+        std::env::set_var(NAME, "off");
+        assert_eq!(source.get(), Some(ColorChoice::Never));
+    }
+
+    #[test]
+    fn test_is_falsy() {
+        for s in ["0", "false", "FALSE", "No", "off"] {
+            assert!(is_falsy(s));
+        }
+        for s in ["", "1", "true", "yes", "On", "nope"] {
+            assert!(!is_falsy(s));
+        }
+    }
+}
@@ -0,0 +1,82 @@
+/*!
+Interoperability with [`simplelog`](https://docs.rs/simplelog/latest/simplelog/).
+
+The terminal logger of `simplelog` writes through `termcolor`, whose `Auto` choice does not check
+whether the stream is a TTY, hence logs redirected to a file would contain escape sequences.
+The functions in this module compute the color choice of the logger from the resolved [`ColorChoice`]
+and the streams selected by the [`TerminalMode`], so that the logs follow the same policy as the output.
+
+```no_run
+use simplelog::{CombinedLogger, Config, LevelFilter, TerminalMode};
+
+# let cli_color = None;
+let logger = should_color::simplelog::term_logger(
+    cli_color,
+    LevelFilter::Info,
+    Config::default(),
+    TerminalMode::Mixed,
+);
+CombinedLogger::init(vec![logger]).expect("the logger is initialized only once");
+```
+*/
+
+use ::simplelog::{Config, LevelFilter, TermLogger, TerminalMode};
+
+use crate::{resolve, ColorChoice};
+
+/**
+Compute the color choice of a [`TermLogger`] writing to the streams selected by `mode`.
+
+The result is computed by [`termcolor::color_choice`](crate::termcolor::color_choice) for the selected stream;
+in [`TerminalMode::Mixed`], which writes to both the standard output and the standard error,
+colors are enabled only if they are enabled for both streams.
+*/
+pub fn color_choice(choice: ColorChoice, mode: TerminalMode) -> ::termcolor::ColorChoice {
+    use crate::termcolor::color_choice;
+
+    match mode {
+        TerminalMode::Stdout => color_choice(choice, atty::Stream::Stdout),
+        TerminalMode::Stderr => color_choice(choice, atty::Stream::Stderr),
+        TerminalMode::Mixed => match color_choice(choice, atty::Stream::Stdout) {
+            ::termcolor::ColorChoice::Never => ::termcolor::ColorChoice::Never,
+            _ => color_choice(choice, atty::Stream::Stderr),
+        },
+    }
+}
+
+/**
+Create a [`TermLogger`] whose color choice is computed by [`color_choice`]
+from [`resolve`]`(cli)` with a default of [`ColorChoice::Auto`].
+*/
+pub fn term_logger(
+    cli: Option<ColorChoice>,
+    level: LevelFilter,
+    config: Config,
+    mode: TerminalMode,
+) -> Box<TermLogger> {
+    let choice = resolve(cli).unwrap_or(ColorChoice::Auto);
+    TermLogger::new(level, config, mode, color_choice(choice, mode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_choice() {
+        for mode in [
+            TerminalMode::Stdout,
+            TerminalMode::Stderr,
+            TerminalMode::Mixed,
+        ] {
+            assert_eq!(
+                color_choice(ColorChoice::Never, mode),
+                ::termcolor::ColorChoice::Never
+            );
+            assert_eq!(
+                color_choice(ColorChoice::Always, mode),
+                ::termcolor::ColorChoice::Always
+            );
+        }
+    }
+}
@@ -0,0 +1,82 @@
+/*!
+Consistent snapshot of the environment variables which affect the resolution.
+
+[`resolve`](crate::resolve) reads the environment variables one at a time,
+hence it may observe an inconsistent mix of values if another thread modifies the environment meanwhile,
+as happens in test harnesses and some plugin hosts.
+An [`EnvSnapshot`] instead reads all the relevant variables in a single pass,
+and then [`resolve_from`](crate::resolve_from) can resolve the color choice any number of times
+without accessing the environment again.
+
+```
+use should_color::{resolve_from, ColorChoice, EnvSnapshot};
+
+let snapshot = EnvSnapshot::from_vars([("NO_COLOR", "1")]);
+# #[cfg(feature = "no_color")]
+assert_eq!(resolve_from(&snapshot, None), Some(ColorChoice::Never));
+assert_eq!(resolve_from(&snapshot, Some(ColorChoice::Always)), Some(ColorChoice::Always));
+```
+*/
+
+use std::ffi::OsStr;
+
+use crate::report::VARIABLES;
+
+/// Snapshot of the values of the environment variables listed in [`VARIABLES`].
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct EnvSnapshot {
+    vars: Vec<(&'static str, String)>,
+}
+
+impl EnvSnapshot {
+    /// Capture the current values of the environment variables in a single pass.
+    pub fn capture() -> EnvSnapshot {
+        EnvSnapshot::from_vars(std::env::vars_os())
+    }
+
+    /**
+    Create a snapshot from the given pairs of names and values, such as the environment of a child process.
+
+    The variables which are not consulted by this crate are ignored,
+    and the values which are not valid Unicode are converted lossily.
+    */
+    pub fn from_vars<K: AsRef<OsStr>, V: AsRef<OsStr>>(
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> EnvSnapshot {
+        let vars = vars
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let name = VARIABLES.iter().find(|&&n| name.as_ref() == n)?;
+                Some((*name, value.as_ref().to_string_lossy().into_owned()))
+            })
+            .collect();
+        EnvSnapshot { vars }
+    }
+
+    /// Get the captured value of the variable `name`, or `None` if it was unset or is not consulted.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Same as [`EnvSnapshot::get`], but owned, as the lookups of the environment are.
+    pub(crate) fn var(&self, name: &str) -> Option<String> {
+        self.get(name).map(str::to_owned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_vars() {
+        let snapshot = EnvSnapshot::from_vars([("CLICOLOR", "0"), ("HOME", "/root"), ("TERM", "")]);
+        assert_eq!(snapshot.get("CLICOLOR"), Some("0"));
+        assert_eq!(snapshot.get("HOME"), None);
+        assert_eq!(snapshot.get("TERM"), Some(""));
+        assert_eq!(snapshot.get("NO_COLOR"), None);
+    }
+}
@@ -0,0 +1,22 @@
+/*!
+Re-exports of the built-in sources, suitable to feed [`resolve_iter`](crate::resolve_iter).
+
+Every function of this module reads a single setting and returns the color choice which it expresses,
+or `None` if it expresses no preference.
+*/
+
+pub use crate::accessibility::accessibility;
+#[cfg(feature = "cargo_term_color")]
+pub use crate::cargo_term_color;
+#[cfg(feature = "clicolor")]
+pub use crate::clicolor;
+#[cfg(feature = "clicolor_force")]
+pub use crate::clicolor_force;
+#[cfg(feature = "env_logger")]
+pub use crate::env_logger::rust_log_style;
+#[cfg(feature = "no_color")]
+pub use crate::no_color;
+pub use crate::overrides::current_override;
+pub use crate::terminal::emacs_color;
+#[cfg(feature = "terminfo")]
+pub use crate::terminfo::terminfo;
@@ -0,0 +1,152 @@
+/*!
+Pure implementation of the specification, independent of the environment.
+
+The functions of this module interpret the values of the environment variables
+without reading them, hence they are available without the
+<span class="stab portability"><code>std</code></span> feature and never allocate.
+This allows to reuse the same logic in environments where the variables are obtained by other means,
+such as embedded shells or custom runtimes.
+*/
+
+use crate::ColorChoice;
+
+/**
+Interpret a value of the `NO_COLOR` environment variable, where `None` means that it is not set.
+
+See [`no_color`](crate::no_color) for the meaning of the values.
+*/
+pub fn no_color(value: Option<&str>) -> Option<ColorChoice> {
+    match value {
+        Some(s) if !s.is_empty() => Some(ColorChoice::Never),
+        _ => None,
+    }
+}
+
+/**
+Interpret a value of the `NO_COLOR` environment variable according to the letter of the specification,
+where `None` means that it is not set.
+
+Unlike [`no_color`], the empty string also yields `Some(`[`ColorChoice::Never`]`)`,
+since the presence of the variable, regardless of its value, disables colors.
+
+```
+# use should_color::{spec::no_color_strict, ColorChoice};
+assert_eq!(no_color_strict(Some("")), Some(ColorChoice::Never));
+assert_eq!(no_color_strict(None), None);
+```
+*/
+pub fn no_color_strict(value: Option<&str>) -> Option<ColorChoice> {
+    value.map(|_| ColorChoice::Never)
+}
+
+/**
+Interpret a value of the `CLICOLOR` environment variable, where `None` means that it is not set.
+
+See [`clicolor`](crate::clicolor) for the meaning of the values.
+*/
+pub fn clicolor(value: Option<&str>) -> Option<ColorChoice> {
+    match value {
+        Some("0") => Some(ColorChoice::Never),
+        Some(s) if !s.is_empty() => Some(ColorChoice::Auto),
+        _ => None,
+    }
+}
+
+/**
+Interpret a value of the `CLICOLOR_FORCE` environment variable, where `None` means that it is not set.
+
+See [`clicolor_force`](crate::clicolor_force) for the meaning of the values.
+*/
+pub fn clicolor_force(value: Option<&str>) -> Option<ColorChoice> {
+    match value {
+        Some(s) if !s.is_empty() && s != "0" => Some(ColorChoice::Always),
+        _ => None,
+    }
+}
+
+/**
+Interpret a value of the `CARGO_TERM_COLOR` environment variable, where `None` means that it is not set.
+
+See [`cargo_term_color`](crate::cargo_term_color) for the meaning of the values.
+*/
+pub fn cargo_term_color(value: Option<&str>) -> Option<ColorChoice> {
+    match value {
+        Some("never") => Some(ColorChoice::Never),
+        Some("auto") => Some(ColorChoice::Auto),
+        Some("always") => Some(ColorChoice::Always),
+        _ => None,
+    }
+}
+
+/**
+Resolve the output color choice from the values of the environment variables
+and an explicit CLI preference.
+
+This is the same as [`resolve`](crate::resolve), except that the values of
+`CLICOLOR_FORCE`, `CARGO_TERM_COLOR`, `CLICOLOR`, and `NO_COLOR` are passed explicitly,
+where `None` means that the variable is not set.
+The values of the variables whose feature is disabled are ignored,
+and the terminfo database is not consulted.
+
+```
+# use should_color::{spec::resolve_values, ColorChoice};
+# #[cfg(feature = "no_color")]
+assert_eq!(resolve_values(None, None, None, None, Some("1")), Some(ColorChoice::Never));
+# #[cfg(feature = "clicolor")]
+assert_eq!(resolve_values(None, None, None, Some("1"), Some("1")), Some(ColorChoice::Auto));
+# #[cfg(feature = "cargo_term_color")]
+assert_eq!(resolve_values(None, None, Some("always"), None, Some("1")), Some(ColorChoice::Always));
+assert_eq!(resolve_values(Some(ColorChoice::Always), None, None, None, Some("1")), Some(ColorChoice::Always));
+```
+*/
+#[allow(unused_variables)]
+pub fn resolve_values(
+    cli: Option<ColorChoice>,
+    clicolor_force: Option<&str>,
+    cargo_term_color: Option<&str>,
+    clicolor: Option<&str>,
+    no_color: Option<&str>,
+) -> Option<ColorChoice> {
+    #[cfg(feature = "clicolor_force")]
+    let choice = self::clicolor_force(clicolor_force).or(cli);
+    #[cfg(not(feature = "clicolor_force"))]
+    let choice = cli;
+
+    #[cfg(feature = "cargo_term_color")]
+    let choice = choice.or_else(|| self::cargo_term_color(cargo_term_color));
+
+    #[cfg(feature = "clicolor")]
+    let choice = choice.or_else(|| self::clicolor(clicolor));
+
+    #[cfg(feature = "no_color")]
+    let choice = choice.or_else(|| self::no_color(no_color));
+
+    choice
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values() {
+        assert_eq!(no_color(None), None);
+        assert_eq!(no_color(Some("")), None);
+        assert_eq!(no_color(Some("0")), Some(ColorChoice::Never));
+
+        assert_eq!(clicolor(None), None);
+        assert_eq!(clicolor(Some("")), None);
+        assert_eq!(clicolor(Some("0")), Some(ColorChoice::Never));
+        assert_eq!(clicolor(Some("1")), Some(ColorChoice::Auto));
+
+        assert_eq!(clicolor_force(None), None);
+        assert_eq!(clicolor_force(Some("")), None);
+        assert_eq!(clicolor_force(Some("0")), None);
+        assert_eq!(clicolor_force(Some("1")), Some(ColorChoice::Always));
+
+        assert_eq!(cargo_term_color(None), None);
+        assert_eq!(cargo_term_color(Some("")), None);
+        assert_eq!(cargo_term_color(Some("never")), Some(ColorChoice::Never));
+        assert_eq!(cargo_term_color(Some("Always")), None);
+    }
+}
@@ -0,0 +1,435 @@
+/*!
+Writer adapter which strips ANSI escape sequences when coloring is disabled.
+
+With [`MaybeAnsi`] an application can unconditionally emit escape sequences,
+and still honor the color choice of the user.
+
+```
+use std::io::Write;
+use should_color::strip::MaybeAnsi;
+
+let mut output = MaybeAnsi::new(Vec::new(), false);
+write!(output, "\x1b[1;31merror\x1b[0m: something went wrong")?;
+assert_eq!(output.into_inner(), b"error: something went wrong");
+# Ok::<(), std::io::Error>(())
+```
+
+If the <span class="stab portability"><code>windows</code></span> and <span class="stab portability"><code>stream</code></span>
+features are enabled, on legacy Windows consoles which do not process ANSI escape sequences
+(see [`windows::supports_ansi`](crate::windows::supports_ansi)) the basic SGR sequences
+are translated into calls to `SetConsoleTextAttribute` instead of being stripped
+(see [`MaybeAnsi::legacy_console`]).
+*/
+
+use std::io::{self, Write};
+
+#[cfg(feature = "stream")]
+use crate::ColorChoice;
+
+/// State of the parser of escape sequences, preserved across writes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum State {
+    /// Plain text.
+    Ground,
+    /// After `ESC`.
+    Escape,
+    /// After `ESC` and some intermediate bytes.
+    EscapeIntermediate,
+    /// Inside a control sequence (`ESC [`).
+    Csi,
+    /// Inside a control string (`ESC ]`, `ESC P`, `ESC X`, `ESC ^`, `ESC _`).
+    String,
+    /// After `ESC` inside a control string, possibly terminating it.
+    StringEscape,
+}
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+impl State {
+    /// Advance the state with `byte`, returning whether the byte belongs to the plain text.
+    pub(crate) fn advance(&mut self, byte: u8) -> bool {
+        let (next, plain) = match (*self, byte) {
+            (State::Ground, ESC) => (State::Escape, false),
+            (State::Ground, _) => (State::Ground, true),
+            (State::Escape, b'[') => (State::Csi, false),
+            (State::Escape, b']' | b'P' | b'X' | b'^' | b'_') => (State::String, false),
+            (State::Escape | State::EscapeIntermediate, 0x20..=0x2f) => {
+                (State::EscapeIntermediate, false)
+            }
+            (State::Escape | State::EscapeIntermediate, _) => (State::Ground, false),
+            (State::Csi, 0x40..=0x7e) => (State::Ground, false),
+            (State::Csi, _) => (State::Csi, false),
+            (State::String, BEL) => (State::Ground, false),
+            (State::String | State::StringEscape, ESC) => (State::StringEscape, false),
+            (State::StringEscape, b'\\') => (State::Ground, false),
+            (State::String | State::StringEscape, _) => (State::String, false),
+        };
+        *self = next;
+        plain
+    }
+}
+
+/// How the escape sequences are treated.
+#[derive(Debug)]
+enum Mode {
+    /// The escape sequences are stripped.
+    Strip,
+    /// All the bytes are passed through.
+    Pass,
+    /// The SGR sequences are translated into console attributes, the other escape sequences are stripped.
+    #[cfg(all(feature = "windows", feature = "stream"))]
+    Console(Console),
+}
+
+/// A legacy Windows console, whose text attributes are set from the SGR sequences.
+#[cfg(all(feature = "windows", feature = "stream"))]
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug)]
+struct Console {
+    stream: atty::Stream,
+    /// The attributes of the console when the writer was created, restored by `SGR 0`.
+    default: u16,
+    /// The attributes currently set.
+    current: u16,
+    /// The parameters of the control sequence being parsed.
+    params: Vec<u8>,
+}
+
+/// Console attributes of the foreground color (the background ones are shifted by 4 bits).
+#[cfg(all(feature = "windows", feature = "stream"))]
+const FOREGROUND: u16 = 0x0f;
+/// Console attribute of the intense foreground color.
+#[cfg(all(feature = "windows", feature = "stream"))]
+const INTENSITY: u16 = 0x08;
+
+/// Convert an ANSI color (`0..8`, red is bit 0) to the console color bits (red is bit 2).
+#[cfg(all(feature = "windows", feature = "stream"))]
+fn console_color(ansi: u16) -> u16 {
+    (ansi & 1) << 2 | (ansi & 2) | (ansi & 4) >> 2
+}
+
+/**
+Compute the console attributes after applying the parameters of an SGR sequence to `current`.
+
+Bold, the foreground and background colors (including the bright ones and the first 16 of the
+256-color palette) and the resets are supported; the other attributes are ignored.
+*/
+#[cfg(all(feature = "windows", feature = "stream"))]
+fn apply_sgr(mut current: u16, default: u16, params: &[u8]) -> u16 {
+    let set_fg = |current: u16, color: u16| current & !FOREGROUND | color;
+    let set_bg = |current: u16, color: u16| current & !(FOREGROUND << 4) | color << 4;
+    // an empty parameter means 0, while the sub-parameters separated by `:` are not supported
+    let mut params = params.split(|&b| b == b';').map(|param| match param {
+        b"" => Some(0),
+        _ => std::str::from_utf8(param).ok()?.parse().ok(),
+    });
+    while let Some(param) = params.next() {
+        current = match param {
+            Some(0) => default,
+            Some(1) => current | INTENSITY,
+            Some(22) => current & !INTENSITY,
+            Some(n @ 30..=37) => set_fg(current, console_color(n - 30) | current & INTENSITY),
+            Some(39) => set_fg(current, default & FOREGROUND),
+            Some(n @ 40..=47) => set_bg(current, console_color(n - 40)),
+            Some(49) => set_bg(current, default >> 4 & FOREGROUND),
+            Some(n @ 90..=97) => set_fg(current, console_color(n - 90) | INTENSITY),
+            Some(n @ 100..=107) => set_bg(current, console_color(n - 100) | INTENSITY),
+            Some(n @ (38 | 48)) => {
+                let color = match params.next().flatten() {
+                    Some(5) => params.next().flatten().filter(|&c| c < 16),
+                    Some(2) => {
+                        // 24-bit colors have no counterpart
+                        params.by_ref().take(3).for_each(drop);
+                        None
+                    }
+                    _ => None,
+                };
+                match color {
+                    Some(c) => {
+                        let color = console_color(c & 7) | if c >= 8 { INTENSITY } else { 0 };
+                        if n == 38 {
+                            set_fg(current, color)
+                        } else {
+                            set_bg(current, color)
+                        }
+                    }
+                    None => current,
+                }
+            }
+            _ => current,
+        };
+    }
+    current
+}
+
+#[cfg(all(feature = "windows", feature = "stream"))]
+#[cfg_attr(not(windows), allow(dead_code))]
+impl Console {
+    /// Get the console of `stream`, if it is a legacy console which does not process ANSI escape sequences.
+    fn new(stream: atty::Stream) -> Option<Console> {
+        #[cfg(windows)]
+        {
+            use windows_sys::Win32::System::Console::{
+                GetConsoleScreenBufferInfo, CONSOLE_SCREEN_BUFFER_INFO,
+            };
+
+            if crate::windows::supports_ansi() {
+                return None;
+            }
+            // SAFETY: the structure is plain data, for which all zeros is a valid value.
+            let mut info: CONSOLE_SCREEN_BUFFER_INFO = unsafe { std::mem::zeroed() };
+            // SAFETY: `info` is a valid pointer, and an invalid handle is reported as an error.
+            if unsafe { GetConsoleScreenBufferInfo(std_handle(stream), &mut info) } == 0 {
+                return None;
+            }
+            Some(Console {
+                stream,
+                default: info.wAttributes,
+                current: info.wAttributes,
+                params: Vec::new(),
+            })
+        }
+        #[cfg(not(windows))]
+        {
+            let _ = stream;
+            None
+        }
+    }
+
+    /// Process a `byte` of a control sequence, applying it to the console if it completes an SGR sequence.
+    fn control(&mut self, inner: &mut impl Write, byte: u8) -> io::Result<()> {
+        match byte {
+            0x40..=0x7e => {
+                if byte == b'm' {
+                    // the text written so far must be displayed with the previous attributes
+                    inner.flush()?;
+                    self.current = apply_sgr(self.current, self.default, &self.params);
+                    self.set_attributes()?;
+                }
+                self.params.clear();
+            }
+            _ => self.params.push(byte),
+        }
+        Ok(())
+    }
+
+    /// Set the current attributes on the console.
+    fn set_attributes(&self) -> io::Result<()> {
+        #[cfg(windows)]
+        {
+            use windows_sys::Win32::System::Console::SetConsoleTextAttribute;
+
+            // SAFETY: an invalid handle is reported as an error.
+            if unsafe { SetConsoleTextAttribute(std_handle(self.stream), self.current) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Get the standard handle of `stream`.
+#[cfg(all(windows, feature = "windows", feature = "stream"))]
+fn std_handle(stream: atty::Stream) -> windows_sys::Win32::Foundation::HANDLE {
+    use windows_sys::Win32::System::Console::{
+        GetStdHandle, STD_ERROR_HANDLE, STD_INPUT_HANDLE, STD_OUTPUT_HANDLE,
+    };
+
+    let id = match stream {
+        atty::Stream::Stdout => STD_OUTPUT_HANDLE,
+        atty::Stream::Stderr => STD_ERROR_HANDLE,
+        atty::Stream::Stdin => STD_INPUT_HANDLE,
+    };
+    // SAFETY: `GetStdHandle` has no preconditions.
+    unsafe { GetStdHandle(id) }
+}
+
+/**
+Writer adapter which strips ANSI escape sequences if coloring is disabled,
+and passes all the bytes through otherwise.
+
+The escape sequences which are recognized are the control sequences (`ESC [ ... final`),
+the control strings (`ESC ] ... ST`, `ESC P ... ST`, etc.) terminated by `ESC \` or `BEL`,
+and the other escape sequences (`ESC intermediate* final`).
+Sequences split across multiple writes are handled correctly.
+*/
+#[derive(Debug)]
+pub struct MaybeAnsi<W> {
+    inner: W,
+    mode: Mode,
+    state: State,
+}
+
+impl<W: Write> MaybeAnsi<W> {
+    /// Wrap `inner` so that escape sequences are stripped if `enabled` is `false`.
+    pub fn new(inner: W, enabled: bool) -> MaybeAnsi<W> {
+        MaybeAnsi::with_mode(inner, if enabled { Mode::Pass } else { Mode::Strip })
+    }
+
+    fn with_mode(inner: W, mode: Mode) -> MaybeAnsi<W> {
+        MaybeAnsi {
+            inner,
+            mode,
+            state: State::Ground,
+        }
+    }
+
+    /**
+    Wrap `inner`, which is assumed to write to `stream`, so that escape sequences are stripped
+    if coloring is disabled according to [`ColorChoice::for_stream`].
+
+    If the <span class="stab portability"><code>windows</code></span> feature is enabled
+    and `stream` is a legacy Windows console, the color choices [`ColorChoice::Auto`] and [`ColorChoice::Always`]
+    translate the SGR sequences into console attributes (see [`MaybeAnsi::legacy_console`]),
+    while [`ColorChoice::AlwaysAnsi`] passes them through.
+    */
+    #[cfg(feature = "stream")]
+    pub fn for_stream(inner: W, choice: ColorChoice, stream: atty::Stream) -> MaybeAnsi<W> {
+        #[cfg(feature = "windows")]
+        if matches!(choice, ColorChoice::Auto | ColorChoice::Always) {
+            if let Some(console) = Console::new(stream) {
+                return MaybeAnsi::with_mode(inner, Mode::Console(console));
+            }
+        }
+        MaybeAnsi::new(inner, choice.for_stream(stream))
+    }
+
+    /**
+    Wrap `inner`, which is assumed to write to `stream`, so that the SGR sequences are translated
+    into calls to `SetConsoleTextAttribute` and the other escape sequences are stripped.
+
+    Bold, the resets, and the foreground and background colors of the 16-color palette are translated,
+    the other attributes are ignored.
+    Returns `None` unless `stream` is a Windows console which does not process ANSI escape sequences
+    (see [`windows::supports_ansi`](crate::windows::supports_ansi)).
+    */
+    #[cfg(all(feature = "windows", feature = "stream"))]
+    pub fn legacy_console(inner: W, stream: atty::Stream) -> Option<MaybeAnsi<W>> {
+        Console::new(stream).map(|console| MaybeAnsi::with_mode(inner, Mode::Console(console)))
+    }
+
+    /// Whether the colors are displayed, either by passing the escape sequences through or by translating them.
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self.mode, Mode::Strip)
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for MaybeAnsi<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Mode::Pass = self.mode {
+            return self.inner.write(buf);
+        }
+        let mut start = 0;
+        for (i, &byte) in buf.iter().enumerate() {
+            #[cfg(all(feature = "windows", feature = "stream"))]
+            let previous = self.state;
+            if !self.state.advance(byte) {
+                if start < i {
+                    self.inner.write_all(&buf[start..i])?;
+                }
+                start = i + 1;
+                #[cfg(all(feature = "windows", feature = "stream"))]
+                if let (Mode::Console(console), State::Csi) = (&mut self.mode, previous) {
+                    console.control(&mut self.inner, byte)?;
+                }
+            }
+        }
+        if start < buf.len() {
+            self.inner.write_all(&buf[start..])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(chunks: &[&[u8]]) -> Vec<u8> {
+        let mut output = MaybeAnsi::new(Vec::new(), false);
+        for chunk in chunks {
+            output.write_all(chunk).unwrap();
+        }
+        output.into_inner()
+    }
+
+    #[test]
+    fn test_enabled() {
+        let mut output = MaybeAnsi::new(Vec::new(), true);
+        output.write_all(b"\x1b[31mred\x1b[0m").unwrap();
+        assert_eq!(output.into_inner(), b"\x1b[31mred\x1b[0m");
+    }
+
+    #[test]
+    fn test_strip() {
+        assert_eq!(strip(&[b"plain text\n"]), b"plain text\n");
+        assert_eq!(strip(&[b"\x1b[1;38;2;255;0;0mred\x1b[m"]), b"red");
+        assert_eq!(
+            strip(&[b"\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07"]),
+            b"link"
+        );
+        assert_eq!(strip(&[b"\x1b(Bascii\x1b=keypad"]), b"asciikeypad");
+        assert_eq!(
+            strip(&["caf\u{e9} \x1b[4m\u{2713}\x1b[24m".as_bytes()]),
+            "caf\u{e9} \u{2713}".as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_strip_split() {
+        assert_eq!(strip(&[b"a\x1b", b"[3", b"1mb\x1b[0", b"m"]), b"ab");
+        assert_eq!(strip(&[b"\x1b]0;title\x1b", b"\\text"]), b"text");
+    }
+
+    #[test]
+    #[cfg(all(feature = "windows", feature = "stream"))]
+    fn test_apply_sgr() {
+        const GRAY: u16 = 0x07;
+        assert_eq!(apply_sgr(GRAY, GRAY, b"31"), 0x04);
+        assert_eq!(apply_sgr(GRAY, GRAY, b"1;32;44"), 0x1a);
+        assert_eq!(apply_sgr(0x1a, GRAY, b""), GRAY);
+        assert_eq!(apply_sgr(0x1a, GRAY, b"22;39"), 0x17);
+        assert_eq!(apply_sgr(GRAY, GRAY, b"93;38;2;1;2;3;4"), 0x0e);
+        assert_eq!(apply_sgr(GRAY, GRAY, b"48;5;9"), 0xc7);
+        assert_eq!(apply_sgr(0x1a, GRAY, b"38:2::1:2:3"), 0x1a);
+    }
+
+    #[test]
+    #[cfg(all(feature = "windows", feature = "stream"))]
+    fn test_translate() {
+        let console = Console {
+            stream: atty::Stream::Stdout,
+            default: 0x07,
+            current: 0x07,
+            params: Vec::new(),
+        };
+        let mut output = MaybeAnsi::with_mode(Vec::new(), Mode::Console(console));
+        output.write_all(b"\x1b[1;3").unwrap();
+        output.write_all(b"4mblue\x1b]0;title\x07").unwrap();
+        assert_eq!(output.get_ref(), b"blue");
+        match &output.mode {
+            Mode::Console(console) => assert_eq!(console.current, 0x09),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+/*!
+Determine whether output should use text styles, such as bold, italic and underline,
+independently of the colors.
+
+Some users want the text styles even when colors are disabled, or vice versa for accessibility.
+The resulting style choice is determined by taking into account,
+in order of priority from higher to lower, the following settings:
+
+- explicit user preference (for instance command line arguments),
+- `NO_STYLE` environment variable,
+- application default choice.
+
+If the final choice is [`StyleChoice::Auto`] and the feature <span class="stab portability"><code>stream</code></span> is enabled,
+the choice can be refined using [`StyleChoice::for_stream`], which makes the styles follow the colors.
+
+```
+use should_color::style::{resolve_style, StyleChoice};
+# #[cfg(feature = "stream")] {
+use should_color::ColorChoice;
+
+# let cli_style = None;
+let style = resolve_style(cli_style).unwrap_or(StyleChoice::Auto);
+let bold = style.for_stream(atty::Stream::Stdout, ColorChoice::Never);
+# }
+```
+
+# `NO_STYLE`
+
+The meaning of the environment variable is the following:
+
+- if not set or `NO_STYLE == ""`: ignore;
+- if set and `NO_STYLE != ""`: [`StyleChoice::Never`].
+*/
+
+/// Name of the `NO_STYLE` environment variable.
+pub const NO_STYLE: &str = "NO_STYLE";
+
+/// Possible style choices for the output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StyleChoice {
+    /// The output will not contain text styles.
+    Never,
+    /// The output will contain text styles if it is colorized.
+    Auto,
+    /// The output will contain text styles, even if it is not colorized.
+    Always,
+}
+
+#[cfg(feature = "stream")]
+impl StyleChoice {
+    /**
+    Determine the style setting for a specific stream.
+
+    If the choice is [`StyleChoice::Never`] or [`StyleChoice::Always`],
+    the result will be `false` and `true` respectively.
+
+    If the choice is [`StyleChoice::Auto`], then the styles follow the colors,
+    as determined by [`ColorChoice::for_stream`](crate::ColorChoice::for_stream) for `color`.
+    */
+    pub fn for_stream(&self, stream: atty::Stream, color: crate::ColorChoice) -> bool {
+        match self {
+            StyleChoice::Never => false,
+            StyleChoice::Always => true,
+            StyleChoice::Auto => color.for_stream(stream),
+        }
+    }
+}
+
+/**
+Get the setting of the `NO_STYLE` environment variable.
+
+The environment variable is treated as follows:
+
+- if not set or `NO_STYLE == ""`: return `None`;
+- if set and `NO_STYLE != ""`: return `Some(`[`StyleChoice::Never`]`)`.
+*/
+pub fn no_style() -> Option<StyleChoice> {
+    match crate::env_var(NO_STYLE) {
+        Some(s) if !s.is_empty() => Some(StyleChoice::Never),
+        _ => None,
+    }
+}
+
+/**
+Resolve the style choice from the environment variables and an explicit CLI preference.
+
+Commonly this function will be called as `resolve_style(cli).unwrap_or(default)`
+to take into account a preference expressed through the CLI arguments
+and the default behavior of the application.
+*/
+pub fn resolve_style(cli: Option<StyleChoice>) -> Option<StyleChoice> {
+    cli.or_else(no_style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_style() {
+        std::env::remove_var(NO_STYLE);
+        assert_eq!(no_style(), None);
+        assert_eq!(resolve_style(None), None);
+
+        std::env::set_var(NO_STYLE, "");
+        assert_eq!(no_style(), None);
+
+        std::env::set_var(NO_STYLE, "1");
+        assert_eq!(resolve_style(None), Some(StyleChoice::Never));
+        assert_eq!(
+            resolve_style(Some(StyleChoice::Always)),
+            Some(StyleChoice::Always)
+        );
+        std::env::remove_var(NO_STYLE);
+    }
+
+    #[test]
+    #[cfg(feature = "stream")]
+    fn test_for_stream() {
+        use crate::ColorChoice;
+
+        let stream = atty::Stream::Stdout;
+        assert!(StyleChoice::Always.for_stream(stream, ColorChoice::Never));
+        assert!(!StyleChoice::Never.for_stream(stream, ColorChoice::Always));
+        assert!(StyleChoice::Auto.for_stream(stream, ColorChoice::Always));
+        assert!(!StyleChoice::Auto.for_stream(stream, ColorChoice::Never));
+    }
+}
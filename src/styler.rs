@@ -0,0 +1,317 @@
+/*!
+Conditional styling of text written to a [`core::fmt::Write`], such as a `String`.
+
+Not all output goes straight to a stream: colored strings are often built for later display.
+A [`Styler`] wraps a `fmt::Write` and emits the ANSI escape sequences of a [`Style`]
+only if coloring is enabled, so that the strings respect the color choice of the user.
+Colors which exceed the color depth of the terminal can be adapted with `Styler::with_depth`
+(with the <span class="stab portability"><code>std</code></span> feature).
+
+This module is available also without the standard library.
+
+```
+use should_color::styler::{Color, Style, Styler};
+
+let error = Style::new().bold().fg(Color::RED);
+
+let mut plain = String::new();
+Styler::new(&mut plain, false).styled(error, "error")?;
+assert_eq!(plain, "error");
+
+let mut colored = String::new();
+Styler::new(&mut colored, true).styled(error, "error")?;
+assert_eq!(colored, "\x1b[1;31merror\x1b[0m");
+# Ok::<(), core::fmt::Error>(())
+```
+*/
+
+use core::fmt::{self, Write};
+
+/// A color of the text or of the background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// One of the 16 ANSI colors: `0..8` are the basic colors, and `8..16` their bright variants.
+    Ansi(u8),
+    /// A color of the 256-color palette.
+    Fixed(u8),
+    /// A 24-bit RGB color.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// The basic black color.
+    pub const BLACK: Color = Color::Ansi(0);
+    /// The basic red color.
+    pub const RED: Color = Color::Ansi(1);
+    /// The basic green color.
+    pub const GREEN: Color = Color::Ansi(2);
+    /// The basic yellow color.
+    pub const YELLOW: Color = Color::Ansi(3);
+    /// The basic blue color.
+    pub const BLUE: Color = Color::Ansi(4);
+    /// The basic magenta color.
+    pub const MAGENTA: Color = Color::Ansi(5);
+    /// The basic cyan color.
+    pub const CYAN: Color = Color::Ansi(6);
+    /// The basic white color.
+    pub const WHITE: Color = Color::Ansi(7);
+
+    /// Write the SGR parameters of the color, where `base` is 30 for the foreground and 40 for the background.
+    fn write_sgr(self, f: &mut impl Write, base: u8) -> fmt::Result {
+        match self {
+            Color::Ansi(n @ 0..=7) => write!(f, "{}", base + n),
+            Color::Ansi(n) => write!(f, "{}", base + 60 + (n & 7)),
+            Color::Fixed(n) => write!(f, "{};5;{n}", base + 8),
+            Color::Rgb(r, g, b) => write!(f, "{};2;{r};{g};{b}", base + 8),
+        }
+    }
+}
+
+/// The effects of a style, as the pairs of flag and SGR parameter.
+const EFFECTS: [(u8, u8); 5] = [
+    (Style::BOLD, 1),
+    (Style::DIM, 2),
+    (Style::ITALIC, 3),
+    (Style::UNDERLINE, 4),
+    (Style::STRIKETHROUGH, 9),
+];
+
+/// The colors and the effects of some text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    effects: u8,
+}
+
+impl Style {
+    const BOLD: u8 = 1 << 0;
+    const DIM: u8 = 1 << 1;
+    const ITALIC: u8 = 1 << 2;
+    const UNDERLINE: u8 = 1 << 3;
+    const STRIKETHROUGH: u8 = 1 << 4;
+
+    /// Create the plain style, without colors and effects.
+    pub const fn new() -> Style {
+        Style {
+            fg: None,
+            bg: None,
+            effects: 0,
+        }
+    }
+
+    /// Set the color of the text.
+    pub const fn fg(mut self, color: Color) -> Style {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Set the color of the background.
+    pub const fn bg(mut self, color: Color) -> Style {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Add the bold effect.
+    pub const fn bold(mut self) -> Style {
+        self.effects |= Style::BOLD;
+        self
+    }
+
+    /// Add the dim effect.
+    pub const fn dim(mut self) -> Style {
+        self.effects |= Style::DIM;
+        self
+    }
+
+    /// Add the italic effect.
+    pub const fn italic(mut self) -> Style {
+        self.effects |= Style::ITALIC;
+        self
+    }
+
+    /// Add the underline effect.
+    pub const fn underline(mut self) -> Style {
+        self.effects |= Style::UNDERLINE;
+        self
+    }
+
+    /// Add the strikethrough effect.
+    pub const fn strikethrough(mut self) -> Style {
+        self.effects |= Style::STRIKETHROUGH;
+        self
+    }
+
+    /// Whether the style has no colors and no effects.
+    pub const fn is_plain(&self) -> bool {
+        self.fg.is_none() && self.bg.is_none() && self.effects == 0
+    }
+
+    /// Adapt the colors to the color depth, if any.
+    #[cfg(feature = "std")]
+    fn adapt(mut self, depth: Option<crate::depth::ColorDepth>) -> Style {
+        if let Some(depth) = depth {
+            self.fg = self.fg.and_then(|color| depth.adapt(color));
+            self.bg = self.bg.and_then(|color| depth.adapt(color));
+        }
+        self
+    }
+
+    /// Write the SGR sequence which enables the style.
+    fn write_prefix(&self, f: &mut impl Write) -> fmt::Result {
+        f.write_str("\x1b[")?;
+        let mut first = true;
+        let mut separator = |f: &mut dyn Write| {
+            if first {
+                first = false;
+                Ok(())
+            } else {
+                f.write_char(';')
+            }
+        };
+        for (flag, param) in EFFECTS {
+            if self.effects & flag != 0 {
+                separator(f)?;
+                write!(f, "{param}")?;
+            }
+        }
+        if let Some(color) = self.fg {
+            separator(f)?;
+            color.write_sgr(f, 30)?;
+        }
+        if let Some(color) = self.bg {
+            separator(f)?;
+            color.write_sgr(f, 40)?;
+        }
+        f.write_char('m')
+    }
+}
+
+/**
+Writer adapter which styles the text only if coloring is enabled.
+
+The plain writes through [`fmt::Write`] are forwarded unchanged,
+while [`Styler::styled`] surrounds the text with the escape sequences of a style.
+*/
+#[derive(Debug)]
+pub struct Styler<W> {
+    inner: W,
+    enabled: bool,
+    #[cfg(feature = "std")]
+    depth: Option<crate::depth::ColorDepth>,
+}
+
+impl<W: Write> Styler<W> {
+    /// Wrap `inner` so that the text is styled only if `enabled` is `true`.
+    pub fn new(inner: W, enabled: bool) -> Styler<W> {
+        Styler {
+            inner,
+            enabled,
+            #[cfg(feature = "std")]
+            depth: None,
+        }
+    }
+
+    /**
+    Wrap `inner` so that the text is styled only if coloring is enabled for `stream`
+    according to [`ColorChoice::for_stream`](crate::ColorChoice::for_stream).
+    */
+    #[cfg(feature = "stream")]
+    pub fn for_stream(inner: W, choice: crate::ColorChoice, stream: atty::Stream) -> Styler<W> {
+        Styler::new(inner, choice.for_stream(stream))
+    }
+
+    /**
+    Adapt the colors which exceed `depth` to the nearest supported ones
+    (see [`ColorDepth::adapt`](crate::depth::ColorDepth::adapt)), keeping the effects.
+
+    With [`ColorDepth::NoColor`](crate::depth::ColorDepth::NoColor) only the effects are emitted.
+    */
+    #[cfg(feature = "std")]
+    pub fn with_depth(mut self, depth: crate::depth::ColorDepth) -> Styler<W> {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Whether the text is styled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Write `text` with `style` if coloring is enabled, and plainly otherwise.
+    pub fn styled(&mut self, style: Style, text: impl fmt::Display) -> fmt::Result {
+        #[cfg(feature = "std")]
+        let style = style.adapt(self.depth);
+        if !self.enabled || style.is_plain() {
+            return write!(self.inner, "{text}");
+        }
+        style.write_prefix(&mut self.inner)?;
+        write!(self.inner, "{text}")?;
+        self.inner.write_str("\x1b[0m")
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for Styler<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_str(s)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn render(enabled: bool, style: Style) -> String {
+        let mut output = String::new();
+        Styler::new(&mut output, enabled)
+            .styled(style, "text")
+            .unwrap();
+        output
+    }
+
+    #[test]
+    fn test_styled() {
+        let style = Style::new()
+            .italic()
+            .fg(Color::Ansi(9))
+            .bg(Color::Fixed(17));
+        assert_eq!(render(false, style), "text");
+        assert_eq!(render(true, Style::new()), "text");
+        assert_eq!(render(true, style), "\x1b[3;91;48;5;17mtext\x1b[0m");
+        assert_eq!(
+            render(true, Style::new().fg(Color::Rgb(1, 2, 3))),
+            "\x1b[38;2;1;2;3mtext\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_with_depth() {
+        use crate::depth::ColorDepth;
+
+        let mut output = String::new();
+        let mut styler = Styler::new(&mut output, true).with_depth(ColorDepth::Ansi16);
+        styler
+            .styled(Style::new().bold().fg(Color::Rgb(1, 2, 3)), "a")
+            .unwrap();
+        write!(styler, "-").unwrap();
+        styler
+            .styled(Style::new().fg(Color::Fixed(1)), "b")
+            .unwrap();
+        assert_eq!(output, "\x1b[1;30ma\x1b[0m-\x1b[31mb\x1b[0m");
+    }
+}
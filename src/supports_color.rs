@@ -0,0 +1,104 @@
+/*!
+API compatible with the [`supports-color`](https://docs.rs/supports-color/latest/supports_color/) crate.
+
+Projects using `supports-color` can migrate by replacing the import,
+gaining the handling of `CLICOLOR`, `CLICOLOR_FORCE`, and the other settings of this crate:
+
+```
+use should_color::supports_color::{on, Stream};
+
+if let Some(support) = on(Stream::Stdout) {
+    if support.has_16m {
+        println!("\x1b[38;2;255;128;0mtruecolor\x1b[0m");
+    } else if support.has_basic {
+        println!("\x1b[33mbasic\x1b[0m");
+    }
+}
+```
+*/
+
+use std::sync::OnceLock;
+
+use crate::depth::{resolve_depth, ColorDepth};
+use crate::ColorChoice;
+
+pub use atty::Stream;
+
+/// Level of color support of a stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ColorLevel {
+    /// Level of support: `1` for basic colors, `2` for 256 colors, and `3` for 24-bit colors.
+    pub level: usize,
+    /// Whether the 16 basic colors are supported.
+    pub has_basic: bool,
+    /// Whether the 256-color palette is supported.
+    pub has_256: bool,
+    /// Whether 24-bit RGB colors are supported.
+    pub has_16m: bool,
+}
+
+impl ColorLevel {
+    /// Convert a color depth into a level, or `None` if no colors are supported.
+    pub fn from_depth(depth: ColorDepth) -> Option<ColorLevel> {
+        let level = match depth {
+            ColorDepth::NoColor => return None,
+            ColorDepth::Ansi16 => 1,
+            ColorDepth::Ansi256 => 2,
+            ColorDepth::TrueColor => 3,
+        };
+        Some(ColorLevel {
+            level,
+            has_basic: true,
+            has_256: level >= 2,
+            has_16m: level >= 3,
+        })
+    }
+}
+
+/**
+Determine the level of color support of a stream, or `None` if the output should not be colorized.
+
+The color choice and depth are resolved by [`resolve_depth`] without explicit preferences,
+and [`ColorChoice::Auto`] is refined according to the stream.
+*/
+pub fn on(stream: Stream) -> Option<ColorLevel> {
+    let (choice, depth) = resolve_depth(None, None);
+    if choice.unwrap_or(ColorChoice::Auto).for_stream(stream) {
+        ColorLevel::from_depth(depth)
+    } else {
+        None
+    }
+}
+
+/// Same as [`on`], but the result is computed only once per stream.
+pub fn on_cached(stream: Stream) -> Option<ColorLevel> {
+    static STDOUT: OnceLock<Option<ColorLevel>> = OnceLock::new();
+    static STDERR: OnceLock<Option<ColorLevel>> = OnceLock::new();
+    static STDIN: OnceLock<Option<ColorLevel>> = OnceLock::new();
+
+    let cache = match stream {
+        Stream::Stdout => &STDOUT,
+        Stream::Stderr => &STDERR,
+        Stream::Stdin => &STDIN,
+    };
+    *cache.get_or_init(|| on(stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_depth() {
+        assert_eq!(ColorLevel::from_depth(ColorDepth::NoColor), None);
+        assert_eq!(
+            ColorLevel::from_depth(ColorDepth::Ansi256),
+            Some(ColorLevel {
+                level: 2,
+                has_basic: true,
+                has_256: true,
+                has_16m: false,
+            })
+        );
+    }
+}
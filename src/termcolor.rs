@@ -0,0 +1,236 @@
+/*!
+Interoperability with [`termcolor`](https://docs.rs/termcolor/latest/termcolor/).
+
+[`ColorChoice`] can be converted to and from
+[`termcolor::ColorChoice`](https://docs.rs/termcolor/latest/termcolor/enum.ColorChoice.html).
+Notice however that [`termcolor::ColorChoice::Auto`](https://docs.rs/termcolor/latest/termcolor/enum.ColorChoice.html#variant.Auto)
+does not check whether the stream is a TTY, hence it is usually preferable to use [`color_choice`],
+which takes the stream into account, or the pre-configured [`stdout`] and [`stderr`].
+
+```no_run
+use std::io::Write;
+use termcolor::{Color, ColorSpec, WriteColor};
+
+let mut stdout = should_color::termcolor::stdout();
+stdout.set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
+writeln!(stdout, "green text")?;
+stdout.reset()?;
+# Ok::<(), std::io::Error>(())
+```
+
+Libraries which accept any [`WriteColor`] can wrap it in a [`MaybeColor`],
+which ignores the styles when coloring is disabled for the stream.
+*/
+
+use std::io::{self, Write};
+
+use ::termcolor::{ColorSpec, HyperlinkSpec, StandardStream, WriteColor};
+
+use crate::{resolve, ColorChoice};
+
+impl From<ColorChoice> for ::termcolor::ColorChoice {
+    fn from(color_choice: ColorChoice) -> ::termcolor::ColorChoice {
+        match color_choice {
+            ColorChoice::Never => ::termcolor::ColorChoice::Never,
+            ColorChoice::Auto => ::termcolor::ColorChoice::Auto,
+            ColorChoice::Always => ::termcolor::ColorChoice::Always,
+            ColorChoice::AlwaysAnsi => ::termcolor::ColorChoice::AlwaysAnsi,
+        }
+    }
+}
+
+impl From<::termcolor::ColorChoice> for ColorChoice {
+    fn from(color_choice: ::termcolor::ColorChoice) -> ColorChoice {
+        match color_choice {
+            ::termcolor::ColorChoice::Never => ColorChoice::Never,
+            ::termcolor::ColorChoice::Auto => ColorChoice::Auto,
+            ::termcolor::ColorChoice::Always => ColorChoice::Always,
+            ::termcolor::ColorChoice::AlwaysAnsi => ColorChoice::AlwaysAnsi,
+        }
+    }
+}
+
+/**
+Compute the [`termcolor::ColorChoice`](https://docs.rs/termcolor/latest/termcolor/enum.ColorChoice.html)
+for a specific stream.
+
+The result is `Always` or `Never` according to [`ColorChoice::for_stream`],
+except that [`ColorChoice::AlwaysAnsi`] is preserved.
+*/
+pub fn color_choice(choice: ColorChoice, stream: atty::Stream) -> ::termcolor::ColorChoice {
+    if choice == ColorChoice::AlwaysAnsi {
+        ::termcolor::ColorChoice::AlwaysAnsi
+    } else if choice.for_stream(stream) {
+        ::termcolor::ColorChoice::Always
+    } else {
+        ::termcolor::ColorChoice::Never
+    }
+}
+
+/**
+Create a [`StandardStream`] for the standard output, configured according to
+[`resolve`] without an explicit CLI preference and a default value of [`ColorChoice::Auto`].
+*/
+pub fn stdout() -> StandardStream {
+    let choice = resolve(None).unwrap_or(ColorChoice::Auto);
+    StandardStream::stdout(color_choice(choice, atty::Stream::Stdout))
+}
+
+/**
+Create a [`StandardStream`] for the standard error, configured according to
+[`resolve`] without an explicit CLI preference and a default value of [`ColorChoice::Auto`].
+*/
+pub fn stderr() -> StandardStream {
+    let choice = resolve(None).unwrap_or(ColorChoice::Auto);
+    StandardStream::stderr(color_choice(choice, atty::Stream::Stderr))
+}
+
+/**
+Adapter of a [`WriteColor`] which forwards the writes,
+but ignores the styles and the hyperlinks if coloring is disabled.
+
+```
+use std::io::Write;
+use termcolor::{Ansi, Color, ColorSpec, WriteColor};
+use should_color::termcolor::MaybeColor;
+
+let mut output = MaybeColor::new(Ansi::new(Vec::new()), false);
+output.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+write!(output, "error")?;
+output.reset()?;
+assert_eq!(output.into_inner().into_inner(), b"error");
+# Ok::<(), std::io::Error>(())
+```
+*/
+#[derive(Debug)]
+pub struct MaybeColor<W> {
+    inner: W,
+    enabled: bool,
+}
+
+impl<W: WriteColor> MaybeColor<W> {
+    /// Wrap `inner` so that the styles are ignored if `enabled` is `false`.
+    pub fn new(inner: W, enabled: bool) -> MaybeColor<W> {
+        MaybeColor { inner, enabled }
+    }
+
+    /**
+    Wrap `inner`, which is assumed to write to `stream`, so that the styles are ignored
+    if coloring is disabled according to [`ColorChoice::for_stream`].
+    */
+    pub fn for_stream(inner: W, choice: ColorChoice, stream: atty::Stream) -> MaybeColor<W> {
+        MaybeColor::new(inner, choice.for_stream(stream))
+    }
+
+    /// Whether the styles are forwarded.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: WriteColor> Write for MaybeColor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: WriteColor> WriteColor for MaybeColor<W> {
+    fn supports_color(&self) -> bool {
+        self.enabled && self.inner.supports_color()
+    }
+
+    fn supports_hyperlinks(&self) -> bool {
+        self.enabled && self.inner.supports_hyperlinks()
+    }
+
+    fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
+        if self.enabled {
+            self.inner.set_color(spec)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn set_hyperlink(&mut self, link: &HyperlinkSpec) -> io::Result<()> {
+        if self.enabled {
+            self.inner.set_hyperlink(link)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        if self.enabled {
+            self.inner.reset()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn is_synchronous(&self) -> bool {
+        self.inner.is_synchronous()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        for choice in [
+            ColorChoice::Never,
+            ColorChoice::Auto,
+            ColorChoice::Always,
+            ColorChoice::AlwaysAnsi,
+        ] {
+            let converted: ::termcolor::ColorChoice = choice.into();
+            assert_eq!(ColorChoice::from(converted), choice);
+        }
+    }
+
+    #[test]
+    fn test_color_choice() {
+        assert_eq!(
+            color_choice(ColorChoice::Never, atty::Stream::Stdout),
+            ::termcolor::ColorChoice::Never
+        );
+        assert_eq!(
+            color_choice(ColorChoice::Always, atty::Stream::Stderr),
+            ::termcolor::ColorChoice::Always
+        );
+        assert_eq!(
+            color_choice(ColorChoice::AlwaysAnsi, atty::Stream::Stderr),
+            ::termcolor::ColorChoice::AlwaysAnsi
+        );
+    }
+
+    #[test]
+    fn test_maybe_color() {
+        let spec = ColorSpec::new().set_bold(true).clone();
+        let mut output = MaybeColor::new(::termcolor::Ansi::new(Vec::new()), true);
+        output.set_color(&spec).unwrap();
+        output.write_all(b"bold").unwrap();
+        assert!(output.supports_color());
+        assert_eq!(output.into_inner().into_inner(), b"\x1b[0m\x1b[1mbold");
+    }
+}
@@ -0,0 +1,165 @@
+/*!
+Bundled table of the color capabilities of common terminals.
+
+The system [terminfo](https://man7.org/linux/man-pages/man5/terminfo.5.html) database
+is often missing in containers and minimal images, hence this module provides a small table
+mapping common values of `TERM` to the number of colors they support.
+It is used as a fallback when the terminfo database is unavailable or has no entry for the terminal,
+both by [`color_depth`](crate::depth::color_depth) and by the terminfo source of [`resolve`](crate::resolve).
+
+```
+use should_color::depth::ColorDepth;
+use should_color::termdb::{lookup, lookup_depth};
+
+assert_eq!(lookup("xterm-256color"), Some(256));
+assert_eq!(lookup_depth("vt100"), Some(ColorDepth::NoColor));
+assert_eq!(lookup("unknown-terminal"), None);
+```
+*/
+
+use crate::depth::ColorDepth;
+use crate::ColorChoice;
+
+/// Number of colors of terminals with 24-bit colors, as reported by the `-direct` terminfo entries.
+const DIRECT: u32 = 0x100_0000;
+
+/// The bundled table, sorted by the value of `TERM`.
+const TABLE: &[(&str, u32)] = &[
+    ("alacritty", 256),
+    ("alacritty-direct", DIRECT),
+    ("ansi", 8),
+    ("cons25", 8),
+    ("contour", 256),
+    ("cygwin", 8),
+    ("dumb", 0),
+    ("eterm-color", 8),
+    ("foot", 256),
+    ("foot-direct", DIRECT),
+    ("gnome", 8),
+    ("gnome-256color", 256),
+    ("konsole", 8),
+    ("konsole-256color", 256),
+    ("konsole-direct", DIRECT),
+    ("linux", 8),
+    ("ms-terminal", 256),
+    ("putty", 8),
+    ("putty-256color", 256),
+    ("rxvt", 8),
+    ("rxvt-256color", 256),
+    ("rxvt-88color", 88),
+    ("rxvt-unicode", 88),
+    ("rxvt-unicode-256color", 256),
+    ("screen", 8),
+    ("screen-16color", 16),
+    ("screen-256color", 256),
+    ("st", 8),
+    ("st-256color", 256),
+    ("st-direct", DIRECT),
+    ("sun", 0),
+    ("tmux", 8),
+    ("tmux-256color", 256),
+    ("tmux-direct", DIRECT),
+    ("vt100", 0),
+    ("vt102", 0),
+    ("vt220", 0),
+    ("vt52", 0),
+    ("vte", 8),
+    ("vte-256color", 256),
+    ("vte-direct", DIRECT),
+    ("wezterm", 256),
+    ("xterm", 8),
+    ("xterm-16color", 16),
+    ("xterm-256color", 256),
+    ("xterm-88color", 88),
+    ("xterm-color", 8),
+    ("xterm-direct", DIRECT),
+    ("xterm-ghostty", 256),
+    ("xterm-kitty", 256),
+    ("xterm-mono", 0),
+];
+
+/// Get the number of colors supported by the terminal named `term`, if it is in the table.
+pub fn lookup(term: &str) -> Option<u32> {
+    TABLE
+        .binary_search_by_key(&term, |&(name, _)| name)
+        .ok()
+        .map(|index| TABLE[index].1)
+}
+
+/// Get the color depth of the terminal named `term`, if it is in the table.
+pub fn lookup_depth(term: &str) -> Option<ColorDepth> {
+    lookup(term).map(ColorDepth::from_max_colors)
+}
+
+/// Get the color depth of the terminal named by the `TERM` environment variable, if it is in the table.
+pub fn termdb_depth() -> Option<ColorDepth> {
+    lookup_depth(&crate::env_var(crate::depth::TERM)?)
+}
+
+/**
+Get the color setting of the terminal according to the table.
+
+The table is treated as follows:
+
+- if the terminal named by `TERM` is not in the table: return `None`;
+- if the terminal supports no colors: return `Some(`[`ColorChoice::Never`]`)`;
+- otherwise: return `None`.
+*/
+pub fn termdb() -> Option<ColorChoice> {
+    match termdb_depth()? {
+        ColorDepth::NoColor => Some(ColorChoice::Never),
+        _ => None,
+    }
+}
+
+/**
+Determine whether the terminal named by `TERM` has no color capability, such as `xterm-mono` or `vt100`.
+
+The capability is looked up in the terminfo database if the
+<span class="stab portability"><code>terminfo</code></span> feature is enabled, and in the bundled table otherwise
+or if the database has no entry for the terminal.
+The buffers of Emacs which render ANSI colors despite `TERM=dumb` are not considered colorless
+(see [`terminal::emacs`](crate::terminal::emacs)).
+
+If the <span class="stab portability"><code>colorless_term</code></span> feature is enabled,
+this check demotes [`ColorChoice::Auto`] in [`ColorChoice::for_stream`](crate::ColorChoice::for_stream).
+*/
+pub fn is_colorless() -> bool {
+    is_colorless_with(crate::env_var)
+}
+
+/// Same as [`is_colorless`], but for the given environment variables.
+pub(crate) fn is_colorless_with(var: impl Fn(&str) -> Option<String>) -> bool {
+    let depth = var(crate::depth::TERM)
+        .filter(|term| !term.is_empty())
+        .and_then(|term| {
+            #[cfg(feature = "terminfo")]
+            let depth = crate::terminfo::terminfo_depth_of(&term);
+            #[cfg(not(feature = "terminfo"))]
+            let depth = None;
+            depth.or_else(|| lookup_depth(&term))
+        });
+    depth == Some(ColorDepth::NoColor)
+        && !crate::terminal::emacs_with(var).is_some_and(|emacs| emacs.renders_ansi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_sorted() {
+        assert!(TABLE.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_lookup_depth() {
+        assert_eq!(lookup_depth("dumb"), Some(ColorDepth::NoColor));
+        assert_eq!(lookup_depth("xterm-mono"), Some(ColorDepth::NoColor));
+        assert_eq!(lookup_depth("linux"), Some(ColorDepth::Ansi16));
+        assert_eq!(lookup_depth("rxvt-unicode"), Some(ColorDepth::Ansi16));
+        assert_eq!(lookup_depth("tmux-256color"), Some(ColorDepth::Ansi256));
+        assert_eq!(lookup_depth("foot-direct"), Some(ColorDepth::TrueColor));
+        assert_eq!(lookup_depth(""), None);
+    }
+}
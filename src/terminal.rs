@@ -0,0 +1,350 @@
+/*!
+Identification of the terminal emulator.
+
+The terminal emulator is identified from the environment variables it sets,
+such as `TERM_PROGRAM`, `KITTY_WINDOW_ID`, `WEZTERM_EXECUTABLE`, `VTE_VERSION`, and `TERM`.
+Moreover terminal multiplexers are detected with [`multiplexer`], SSH sessions with [`is_ssh`],
+the buffers of Emacs with [`emacs`], and integrated development environments with [`ide`].
+The result exposes capability hints, which are taken into account by
+[`color_depth`](crate::depth::color_depth) and by the detection of hyperlinks.
+*/
+
+use crate::ColorChoice;
+
+/// Known terminal emulators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum TerminalKind {
+    /// [Alacritty](https://alacritty.org).
+    Alacritty,
+    /// Terminal.app on macOS.
+    AppleTerminal,
+    /// [DomTerm](https://domterm.org).
+    DomTerm,
+    /// [foot](https://codeberg.org/dnkl/foot).
+    Foot,
+    /// [Ghostty](https://ghostty.org).
+    Ghostty,
+    /// [Hyper](https://hyper.is).
+    Hyper,
+    /// [iTerm2](https://iterm2.com).
+    ITerm2,
+    /// The integrated terminal of the JetBrains IDEs (JediTerm).
+    JetBrains,
+    /// [kitty](https://sw.kovidgoyal.net/kitty/).
+    Kitty,
+    /// [Konsole](https://konsole.kde.org).
+    Konsole,
+    /// [Terminology](https://www.enlightenment.org/about-terminology).
+    Terminology,
+    /// The integrated terminal of Visual Studio Code.
+    VsCode,
+    /// A terminal based on the [VTE](https://gitlab.gnome.org/GNOME/vte) library
+    /// (GNOME Terminal, Tilix, Terminator, etc.), with the value of `VTE_VERSION`.
+    Vte(u32),
+    /// [WezTerm](https://wezfurlong.org/wezterm/).
+    WezTerm,
+    /// [Windows Terminal](https://github.com/microsoft/terminal).
+    WindowsTerminal,
+    /// The terminal emulator could not be identified.
+    Unknown,
+}
+
+impl TerminalKind {
+    /// Whether the terminal emulator is known to support 24-bit colors.
+    pub fn supports_truecolor(&self) -> bool {
+        match self {
+            TerminalKind::AppleTerminal | TerminalKind::Unknown => false,
+            TerminalKind::Vte(version) => *version >= 3600,
+            _ => true,
+        }
+    }
+
+    /// Whether the terminal emulator is known to support OSC 8 hyperlinks.
+    pub fn supports_hyperlinks(&self) -> bool {
+        match self {
+            TerminalKind::AppleTerminal | TerminalKind::JetBrains | TerminalKind::Unknown => false,
+            TerminalKind::Vte(version) => *version >= 5000,
+            _ => true,
+        }
+    }
+}
+
+/// Identify the terminal emulator from the given environment variables.
+fn detect_with(var: impl Fn(&str) -> Option<String>) -> TerminalKind {
+    let is_set = |name| var(name).is_some_and(|value| !value.is_empty());
+
+    if is_set("KITTY_WINDOW_ID") {
+        return TerminalKind::Kitty;
+    }
+    if is_set("WEZTERM_EXECUTABLE") {
+        return TerminalKind::WezTerm;
+    }
+    if is_set("WT_SESSION") {
+        return TerminalKind::WindowsTerminal;
+    }
+    if is_set("KONSOLE_VERSION") {
+        return TerminalKind::Konsole;
+    }
+    if is_set("DOMTERM") {
+        return TerminalKind::DomTerm;
+    }
+    if var("TERMINAL_EMULATOR").is_some_and(|value| value.starts_with("JetBrains")) {
+        return TerminalKind::JetBrains;
+    }
+    match var("TERM_PROGRAM").as_deref() {
+        Some("Apple_Terminal") => return TerminalKind::AppleTerminal,
+        Some("iTerm.app") => return TerminalKind::ITerm2,
+        Some("WezTerm") => return TerminalKind::WezTerm,
+        Some("vscode") => return TerminalKind::VsCode,
+        Some("Hyper") => return TerminalKind::Hyper,
+        Some("ghostty") => return TerminalKind::Ghostty,
+        Some("terminology") => return TerminalKind::Terminology,
+        _ => {}
+    }
+    if let Some(version) = var("VTE_VERSION").and_then(|v| v.parse().ok()) {
+        return TerminalKind::Vte(version);
+    }
+    match var("TERM").as_deref() {
+        Some("xterm-kitty") => TerminalKind::Kitty,
+        Some("alacritty") => TerminalKind::Alacritty,
+        Some("xterm-ghostty") => TerminalKind::Ghostty,
+        Some("wezterm") => TerminalKind::WezTerm,
+        Some(term) if term.starts_with("foot") => TerminalKind::Foot,
+        _ => TerminalKind::Unknown,
+    }
+}
+
+/// Identify the terminal emulator from the environment.
+pub fn detect() -> TerminalKind {
+    detect_with(crate::env_var)
+}
+
+/// Known terminal multiplexers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Multiplexer {
+    /// [tmux](https://github.com/tmux/tmux), detected from the `TMUX` environment variable.
+    Tmux,
+    /// [GNU screen](https://www.gnu.org/software/screen/), detected from the `STY` environment variable.
+    Screen,
+}
+
+/// Detect whether the program is running inside a terminal multiplexer.
+pub fn multiplexer() -> Option<Multiplexer> {
+    let is_set = |name| crate::env_var(name).is_some_and(|value| !value.is_empty());
+
+    if is_set("TMUX") {
+        Some(Multiplexer::Tmux)
+    } else if is_set("STY") {
+        Some(Multiplexer::Screen)
+    } else {
+        None
+    }
+}
+
+/**
+Detect whether the program is running inside an SSH session,
+according to the `SSH_TTY`, `SSH_CONNECTION`, and `SSH_CLIENT` environment variables.
+*/
+pub fn is_ssh() -> bool {
+    ["SSH_TTY", "SSH_CONNECTION", "SSH_CLIENT"]
+        .iter()
+        .any(|name| crate::env_var(name).is_some_and(|value| !value.is_empty()))
+}
+
+/// Integrated development environments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ide {
+    /// [Visual Studio Code](https://code.visualstudio.com),
+    /// detected from `TERM_PROGRAM=vscode` or the `VSCODE_PID` environment variable.
+    VsCode,
+    /// The JetBrains IDEs, detected from `TERMINAL_EMULATOR=JetBrains-JediTerm`
+    /// or the `PYCHARM_HOSTED` environment variable.
+    JetBrains,
+}
+
+/// Identify the integrated development environment from the given environment variables.
+pub(crate) fn ide_with(var: impl Fn(&str) -> Option<String>) -> Option<Ide> {
+    let is_set = |name| var(name).is_some_and(|value| !value.is_empty());
+
+    if var("TERM_PROGRAM").as_deref() == Some("vscode") || is_set("VSCODE_PID") {
+        Some(Ide::VsCode)
+    } else if var("TERMINAL_EMULATOR").is_some_and(|value| value.starts_with("JetBrains"))
+        || is_set("PYCHARM_HOSTED")
+    {
+        Some(Ide::JetBrains)
+    } else {
+        None
+    }
+}
+
+/**
+Detect whether the program is running inside an integrated development environment,
+either in its integrated terminal or in its console for running and debugging programs.
+
+These consoles often are not ttys, but they render ANSI colors:
+see [`Resolver::promote_in_ide`](crate::Resolver::promote_in_ide).
+*/
+pub fn ide() -> Option<Ide> {
+    ide_with(crate::env_var)
+}
+
+/// Kinds of Emacs buffers which run subprocesses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Emacs {
+    /// A `comint` buffer, such as `M-x shell`, which sets `TERM=dumb` but can render ANSI colors.
+    Comint,
+    /// The Emacs shell `M-x eshell`, which renders ANSI colors.
+    Eshell,
+    /// The terminal emulator `M-x term` or `M-x ansi-term`.
+    Term,
+    /// The terminal emulator [vterm](https://github.com/akermu/emacs-libvterm).
+    Vterm,
+    /// Any other buffer, such as `M-x compile`, which is treated as a dumb terminal.
+    Other,
+}
+
+impl Emacs {
+    /// Whether the buffer renders ANSI escape sequences.
+    pub fn renders_ansi(&self) -> bool {
+        !matches!(self, Emacs::Other)
+    }
+}
+
+/// Identify the kind of Emacs buffer from the value of `INSIDE_EMACS`.
+pub(crate) fn emacs_from(inside_emacs: &str) -> Emacs {
+    if inside_emacs == "vterm" || inside_emacs.ends_with(",vterm") {
+        return Emacs::Vterm;
+    }
+    match inside_emacs.split_once(',').map(|(_, kind)| kind) {
+        Some("comint") => Emacs::Comint,
+        Some("eshell") => Emacs::Eshell,
+        Some(kind) if kind.starts_with("term:") => Emacs::Term,
+        _ => Emacs::Other,
+    }
+}
+
+/**
+Detect whether the program is running inside an Emacs buffer,
+according to the `INSIDE_EMACS` environment variable.
+
+Emacs sets the variable to its version followed by the kind of buffer, as in `29.1,comint`,
+while vterm sets it to `vterm`.
+*/
+pub fn emacs() -> Option<Emacs> {
+    emacs_with(crate::env_var)
+}
+
+/// Detect the Emacs buffer hosting the program from the given environment variables.
+pub(crate) fn emacs_with(var: impl Fn(&str) -> Option<String>) -> Option<Emacs> {
+    var("INSIDE_EMACS")
+        .filter(|value| !value.is_empty())
+        .map(|value| emacs_from(&value))
+}
+
+/**
+Get the color setting implied by the Emacs buffer hosting the program.
+
+This distinguishes the buffers which render ANSI colors from genuinely dumb terminals,
+even though some of them, such as `M-x shell`, set `TERM=dumb`:
+
+- if not inside Emacs: return `None`;
+- if the buffer renders ANSI colors (see [`Emacs::renders_ansi`]): return `Some(`[`ColorChoice::Auto`]`)`;
+- otherwise: return `None`.
+
+The source is not part of the default priority order: applications opt in by including
+[`SourceId::Emacs`](crate::SourceId::Emacs) in the order of a [`Resolver`](crate::Resolver).
+In any case, the [`terminfo`](crate::SourceId::Terminfo) source does not disable colors in these buffers.
+*/
+pub fn emacs_color() -> Option<ColorChoice> {
+    emacs()
+        .filter(Emacs::renders_ansi)
+        .map(|_| ColorChoice::Auto)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EnvSnapshot;
+
+    fn detect_from(vars: &[(&str, &str)]) -> TerminalKind {
+        let env = EnvSnapshot::from_vars(vars.iter().copied());
+        detect_with(|name| env.var(name))
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect_from(&[]), TerminalKind::Unknown);
+        assert_eq!(
+            detect_from(&[("TERM", "xterm-256color")]),
+            TerminalKind::Unknown
+        );
+        assert_eq!(
+            detect_from(&[("TERM", "xterm-kitty"), ("KITTY_WINDOW_ID", "1")]),
+            TerminalKind::Kitty
+        );
+        assert_eq!(
+            detect_from(&[("TERM_PROGRAM", "Apple_Terminal")]),
+            TerminalKind::AppleTerminal
+        );
+        assert_eq!(
+            detect_from(&[("TERM_PROGRAM", "vscode"), ("VTE_VERSION", "6003")]),
+            TerminalKind::VsCode
+        );
+        assert_eq!(
+            detect_from(&[("VTE_VERSION", "6003")]),
+            TerminalKind::Vte(6003)
+        );
+        assert_eq!(detect_from(&[("TERM", "foot-extra")]), TerminalKind::Foot);
+        assert_eq!(
+            detect_from(&[("KITTY_WINDOW_ID", "")]),
+            TerminalKind::Unknown
+        );
+    }
+
+    #[test]
+    fn test_ide() {
+        let ide_from = |vars: &[(&str, &str)]| {
+            let env = EnvSnapshot::from_vars(vars.iter().copied());
+            ide_with(|name| env.var(name))
+        };
+        assert_eq!(ide_from(&[]), None);
+        assert_eq!(ide_from(&[("TERM_PROGRAM", "vscode")]), Some(Ide::VsCode));
+        assert_eq!(ide_from(&[("VSCODE_PID", "1234")]), Some(Ide::VsCode));
+        assert_eq!(
+            ide_from(&[("TERMINAL_EMULATOR", "JetBrains-JediTerm")]),
+            Some(Ide::JetBrains)
+        );
+        assert_eq!(ide_from(&[("PYCHARM_HOSTED", "1")]), Some(Ide::JetBrains));
+        assert_eq!(
+            detect_from(&[("TERMINAL_EMULATOR", "JetBrains-JediTerm")]),
+            TerminalKind::JetBrains
+        );
+    }
+
+    #[test]
+    fn test_emacs() {
+        assert_eq!(emacs_from("29.1,comint"), Emacs::Comint);
+        assert_eq!(emacs_from("29.1,eshell"), Emacs::Eshell);
+        assert_eq!(emacs_from("29.1,term:0.96"), Emacs::Term);
+        assert_eq!(emacs_from("vterm"), Emacs::Vterm);
+        assert_eq!(emacs_from("29.1,compile"), Emacs::Other);
+        assert_eq!(emacs_from("t"), Emacs::Other);
+        assert!(Emacs::Comint.renders_ansi());
+        assert!(!Emacs::Other.renders_ansi());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        assert!(TerminalKind::Kitty.supports_truecolor());
+        assert!(TerminalKind::Kitty.supports_hyperlinks());
+        assert!(!TerminalKind::AppleTerminal.supports_truecolor());
+        assert!(TerminalKind::Vte(4000).supports_truecolor());
+        assert!(!TerminalKind::Vte(4000).supports_hyperlinks());
+        assert!(!TerminalKind::Unknown.supports_truecolor());
+    }
+}
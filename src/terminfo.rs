@@ -0,0 +1,59 @@
+/*!
+Capabilities of the terminal according to the [terminfo](https://man7.org/linux/man-pages/man5/terminfo.5.html) database.
+
+The entry of the database is selected according to the `TERM` environment variable.
+It is used both to determine whether the terminal supports colors at all ([`terminfo()`]),
+which is taken into account by [`resolve`](crate::resolve),
+and to determine the color depth ([`terminfo_depth`]), which is taken into account by
+[`color_depth`](crate::depth::color_depth).
+*/
+
+use ::terminfo::{capability as cap, Database};
+
+use crate::depth::ColorDepth;
+use crate::ColorChoice;
+
+/// Color depth described by a terminfo entry.
+fn depth(db: &Database) -> ColorDepth {
+    let max_colors = db.get::<cap::MaxColors>().map_or(0, |n| n.0.max(0) as u32);
+    if db.get::<cap::TrueColor>().is_some() || db.raw("RGB").is_some() {
+        ColorDepth::TrueColor
+    } else if db.get::<cap::SetAForeground>().is_none() {
+        ColorDepth::NoColor
+    } else {
+        ColorDepth::from_max_colors(max_colors)
+    }
+}
+
+/**
+Get the color depth of the terminal according to the terminfo database.
+
+Returns `None` if there is no entry for the terminal.
+The presence of the `Tc` or `RGB` capabilities implies [`ColorDepth::TrueColor`],
+otherwise the absence of the `setaf` capability implies [`ColorDepth::NoColor`],
+otherwise the depth is determined by the `colors` capability.
+*/
+pub fn terminfo_depth() -> Option<ColorDepth> {
+    Database::from_env().ok().map(|db| depth(&db))
+}
+
+/// Same as [`terminfo_depth`], but for the terminal named `term` instead of `TERM`.
+pub(crate) fn terminfo_depth_of(term: &str) -> Option<ColorDepth> {
+    Database::from_name(term).ok().map(|db| depth(&db))
+}
+
+/**
+Get the color setting of the terminal according to the terminfo database.
+
+The database is treated as follows:
+
+- if the entry has no color capability (see [`terminfo_depth`]): return `Some(`[`ColorChoice::Never`]`)`;
+- otherwise: return `None`.
+
+If the database has no entry for the terminal, the bundled table is consulted instead,
+and the buffers of Emacs which render ANSI colors despite `TERM=dumb` are exempted,
+exactly as in [`termdb::is_colorless`](crate::termdb::is_colorless).
+*/
+pub fn terminfo() -> Option<ColorChoice> {
+    crate::termdb::is_colorless_with(crate::env_var).then_some(ColorChoice::Never)
+}
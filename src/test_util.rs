@@ -0,0 +1,124 @@
+/*!
+Utilities to test the color behavior of applications.
+
+The environment variables are shared by the whole process, while the tests run in parallel threads,
+hence tests which set `NO_COLOR` or `CLICOLOR_FORCE` interfere with each other.
+An [`EnvGuard`] holds a global lock for its whole lifetime, so that the tests which use it run one at a time,
+starts from an environment where all the variables consulted by this crate are unset,
+and restores their original values when dropped.
+
+```
+use should_color::test_util::EnvGuard;
+use should_color::{resolve, ColorChoice};
+
+let mut env = EnvGuard::new();
+env.set("NO_COLOR", "1");
+# #[cfg(feature = "no_color")]
+assert_eq!(resolve(None), Some(ColorChoice::Never));
+env.remove("NO_COLOR").set("CLICOLOR_FORCE", "1");
+# #[cfg(feature = "clicolor_force")]
+assert_eq!(resolve(None), Some(ColorChoice::Always));
+```
+
+Notice that only the tests which use an [`EnvGuard`] are serialized:
+tests which access the environment directly may still observe its changes.
+*/
+
+use std::ffi::{OsStr, OsString};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::report::VARIABLES;
+
+/// The lock which serializes the lifetimes of the guards.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/**
+Exclusive access to the environment variables, which are restored when the guard is dropped.
+
+See the [module documentation](self) for an example.
+*/
+#[derive(Debug)]
+pub struct EnvGuard {
+    saved: Vec<(OsString, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl EnvGuard {
+    /**
+    Acquire the global lock, waiting for the other guards to be dropped,
+    and unset all the variables listed in [`VARIABLES`].
+
+    The cache of [`resolve_cached`](crate::resolve_cached) is invalidated.
+    */
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> EnvGuard {
+        // a test which panicked while holding the lock has restored the environment anyway
+        let lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut guard = EnvGuard {
+            saved: Vec::new(),
+            _lock: lock,
+        };
+        for name in VARIABLES {
+            guard.remove(name);
+        }
+        guard
+    }
+
+    /// Remember the original value of the variable `name`, unless it is already remembered.
+    fn save(&mut self, name: &OsStr) {
+        if !self.saved.iter().any(|(saved, _)| saved == name) {
+            self.saved.push((name.to_owned(), std::env::var_os(name)));
+        }
+    }
+
+    /// Set the variable `name` to `value`, invalidating the cache of [`resolve_cached`](crate::resolve_cached).
+    pub fn set(&mut self, name: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> &mut EnvGuard {
+        self.save(name.as_ref());
+        std::env::set_var(name, value);
+        crate::invalidate_cache();
+        self
+    }
+
+    /// Unset the variable `name`, invalidating the cache of [`resolve_cached`](crate::resolve_cached).
+    pub fn remove(&mut self, name: impl AsRef<OsStr>) -> &mut EnvGuard {
+        self.save(name.as_ref());
+        std::env::remove_var(name);
+        crate::invalidate_cache();
+        self
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        for (name, value) in self.saved.drain(..).rev() {
+            match value {
+                Some(value) => std::env::set_var(name, value),
+                None => std::env::remove_var(name),
+            }
+        }
+        crate::invalidate_cache();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore() {
+        const NAME: &str = "SHOULD_COLOR_TEST_UTIL";
+
+        std::env::set_var(NAME, "original");
+        {
+            // `EnvGuard::new` would unset the variables read by the other tests running in parallel
+            let mut env = EnvGuard {
+                saved: Vec::new(),
+                _lock: LOCK.lock().unwrap_or_else(PoisonError::into_inner),
+            };
+            env.set(NAME, "first").set(NAME, "second");
+            assert_eq!(std::env::var(NAME).as_deref(), Ok("second"));
+        }
+        assert_eq!(std::env::var(NAME).as_deref(), Ok("original"));
+        std::env::remove_var(NAME);
+    }
+}
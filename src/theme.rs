@@ -0,0 +1,213 @@
+/*!
+Detection of the background of the terminal, to choose a readable palette.
+
+The only hint which is currently taken into account is the
+[`COLORFGBG`](#colorfgbg) environment variable.
+
+If the <span class="stab portability" title="Available on crate feature `query` only"><code>query</code></span> feature is enabled,
+the terminal can be explicitly interrogated for its background color with [`query_background_color`].
+
+# `COLORFGBG`
+
+This environment variable is set by some terminal emulators (for instance rxvt and Konsole)
+to the indices of the default foreground and background colors in the 16-color palette,
+in the form `fg;bg` or `fg;default;bg`.
+The background is considered dark if its index is between 0 and 6 or equal to 8,
+and light if it is 7 or between 9 and 15.
+*/
+
+/// Name of the `COLORFGBG` environment variable.
+pub const COLORFGBG: &str = "COLORFGBG";
+
+/// Possible backgrounds of the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Theme {
+    /// Light background, which requires dark foreground colors.
+    Light,
+    /// Dark background, which requires light foreground colors.
+    Dark,
+}
+
+/// A color in the RGB color space, with 8 bits per channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    /// Red channel.
+    pub r: u8,
+    /// Green channel.
+    pub g: u8,
+    /// Blue channel.
+    pub b: u8,
+}
+
+impl Rgb {
+    /**
+    Classify the color as a light or dark background,
+    according to its [relative luminance](https://www.w3.org/TR/WCAG21/#dfn-relative-luminance).
+    */
+    pub fn theme(&self) -> Theme {
+        fn linear(c: u8) -> f64 {
+            let c = c as f64 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+        let luminance = 0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b);
+        // the luminance at which black and white text have the same contrast ratio
+        if luminance > 0.179 {
+            Theme::Light
+        } else {
+            Theme::Dark
+        }
+    }
+}
+
+/// Classify the background color from the value of `COLORFGBG`.
+fn parse_colorfgbg(value: &str) -> Option<Theme> {
+    match value.rsplit(';').next()?.parse::<u8>().ok()? {
+        0..=6 | 8 => Some(Theme::Dark),
+        7 | 9..=15 => Some(Theme::Light),
+        _ => None,
+    }
+}
+
+/**
+Get the background of the terminal according to the `COLORFGBG` environment variable.
+
+The environment variable is treated as follows:
+
+- if not set or the background index is missing or invalid: return `None`;
+- if the background index is between 0 and 6 or equal to 8: return `Some(`[`Theme::Dark`]`)`;
+- if the background index is 7 or between 9 and 15: return `Some(`[`Theme::Light`]`)`.
+*/
+pub fn colorfgbg() -> Option<Theme> {
+    parse_colorfgbg(&crate::env_var(COLORFGBG)?)
+}
+
+/**
+Determine the background of the terminal from the known hints in the environment.
+
+Returns `None` if the background cannot be determined,
+in which case the application should pick a palette readable on both backgrounds.
+*/
+pub fn background() -> Option<Theme> {
+    colorfgbg()
+}
+
+/**
+Interrogate the terminal for its background color with an `OSC 11` query.
+
+The query is performed only if `stream` is a TTY,
+and the reply is awaited at most for `timeout` (100 milliseconds are usually sufficient).
+Returns `None` if the terminal does not reply in time or the reply cannot be parsed.
+
+This function writes to and reads from the controlling terminal,
+hence it should not be called while other threads are using it.
+Interrogation is currently supported only on Unix.
+*/
+#[cfg(feature = "query")]
+pub fn query_background_color(stream: atty::Stream, timeout: std::time::Duration) -> Option<Rgb> {
+    if !crate::is_tty(stream) {
+        return None;
+    }
+    let reply =
+        crate::query::query_terminal(b"\x1b]11;?\x07", timeout, crate::query::is_osc_complete)?;
+    parse_osc11_reply(&reply)
+}
+
+/// Parse a reply of the form `ESC ] 11 ; rgb:RRRR/GGGG/BBBB` followed by `BEL` or `ESC \`.
+#[cfg(feature = "query")]
+fn parse_osc11_reply(reply: &[u8]) -> Option<Rgb> {
+    let reply = std::str::from_utf8(reply).ok()?;
+    let start = reply.find("\x1b]11;rgb:")?;
+    let spec = reply[start + 9..]
+        .trim_end_matches('\x07')
+        .trim_end_matches("\x1b\\");
+    let mut channels = spec.split('/').map(|hex| {
+        if hex.is_empty() || hex.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        let max = (1 << (4 * hex.len())) - 1;
+        Some((value * 255 / max) as u8)
+    });
+    let rgb = Rgb {
+        r: channels.next()??,
+        g: channels.next()??,
+        b: channels.next()??,
+    };
+    channels.next().is_none().then_some(rgb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_colorfgbg() {
+        assert_eq!(parse_colorfgbg("15;0"), Some(Theme::Dark));
+        assert_eq!(parse_colorfgbg("0;15"), Some(Theme::Light));
+        assert_eq!(parse_colorfgbg("0;default;7"), Some(Theme::Light));
+        assert_eq!(parse_colorfgbg("7;8"), Some(Theme::Dark));
+        assert_eq!(parse_colorfgbg("7;default"), None);
+        assert_eq!(parse_colorfgbg("7;16"), None);
+        assert_eq!(parse_colorfgbg(""), None);
+    }
+
+    #[test]
+    fn test_rgb_theme() {
+        assert_eq!(Rgb { r: 0, g: 0, b: 0 }.theme(), Theme::Dark);
+        assert_eq!(
+            Rgb {
+                r: 40,
+                g: 44,
+                b: 52
+            }
+            .theme(),
+            Theme::Dark
+        );
+        assert_eq!(
+            Rgb {
+                r: 255,
+                g: 255,
+                b: 255
+            }
+            .theme(),
+            Theme::Light
+        );
+        assert_eq!(
+            Rgb {
+                r: 253,
+                g: 246,
+                b: 227
+            }
+            .theme(),
+            Theme::Light
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "query")]
+    fn test_parse_osc11_reply() {
+        assert_eq!(
+            parse_osc11_reply(b"\x1b]11;rgb:ffff/8080/0000\x1b\\"),
+            Some(Rgb {
+                r: 255,
+                g: 128,
+                b: 0
+            })
+        );
+        assert_eq!(
+            parse_osc11_reply(b"\x1b]11;rgb:f/80/000\x07"),
+            Some(Rgb {
+                r: 255,
+                g: 128,
+                b: 0
+            })
+        );
+        assert_eq!(parse_osc11_reply(b"\x1b]11;rgb:ffff/ffff\x07"), None);
+        assert_eq!(parse_osc11_reply(b"\x1b]10;rgb:0/0/0\x07"), None);
+        assert_eq!(parse_osc11_reply(b""), None);
+    }
+}
@@ -0,0 +1,168 @@
+/*!
+Interoperability with the standard streams of [`tokio`](https://docs.rs/tokio/latest/tokio/).
+
+The wrappers [`tokio::io::Stdout`](https://docs.rs/tokio/latest/tokio/io/struct.Stdout.html) and
+[`tokio::io::Stderr`](https://docs.rs/tokio/latest/tokio/io/struct.Stderr.html) write to the same
+file descriptors (or handles on Windows) as the standard streams of the process,
+hence their color setting is determined by [`ColorChoice::for_stream`].
+The functions [`stdout`] and [`stderr`] return them wrapped in an asynchronous [`MaybeAnsi`],
+which strips the ANSI escape sequences when coloring is disabled,
+so that asynchronous applications can follow the same code path as synchronous ones
+(see [`strip::MaybeAnsi`](crate::strip::MaybeAnsi)).
+
+```no_run
+use tokio::io::AsyncWriteExt;
+
+# async fn run() -> std::io::Result<()> {
+# let cli_color = None;
+let choice = should_color::resolve(cli_color).unwrap_or(should_color::ColorChoice::Auto);
+let mut stdout = should_color::tokio::stdout(choice);
+stdout.write_all(b"\x1b[32mok\x1b[0m\n").await?;
+# Ok(())
+# }
+```
+*/
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use ::tokio::io::AsyncWrite;
+
+use crate::strip::State;
+use crate::ColorChoice;
+
+/**
+Asynchronous writer adapter which strips ANSI escape sequences if coloring is disabled,
+and passes all the bytes through otherwise.
+
+The escape sequences are recognized as in [`strip::MaybeAnsi`](crate::strip::MaybeAnsi),
+including the sequences split across multiple writes.
+Unlike the synchronous adapter, the colors are never translated for legacy Windows consoles.
+*/
+#[derive(Debug)]
+pub struct MaybeAnsi<W> {
+    inner: W,
+    enabled: bool,
+    state: State,
+}
+
+impl<W: AsyncWrite + Unpin> MaybeAnsi<W> {
+    /// Wrap `inner` so that escape sequences are stripped if `enabled` is `false`.
+    pub fn new(inner: W, enabled: bool) -> MaybeAnsi<W> {
+        MaybeAnsi {
+            inner,
+            enabled,
+            state: State::Ground,
+        }
+    }
+
+    /**
+    Wrap `inner`, which is assumed to write to `stream`, so that escape sequences are stripped
+    if coloring is disabled according to [`ColorChoice::for_stream`].
+    */
+    pub fn for_stream(inner: W, choice: ColorChoice, stream: atty::Stream) -> MaybeAnsi<W> {
+        MaybeAnsi::new(inner, choice.for_stream(stream))
+    }
+
+    /// Whether the escape sequences are passed through.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for MaybeAnsi<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.enabled {
+            return Pin::new(&mut this.inner).poll_write(cx, buf);
+        }
+        // the leading escape sequences are consumed immediately
+        let mut skipped = 0;
+        while skipped < buf.len() {
+            let mut state = this.state;
+            if state.advance(buf[skipped]) {
+                break;
+            }
+            this.state = state;
+            skipped += 1;
+        }
+        // the state is committed only for the plain bytes which are actually written
+        let mut state = this.state;
+        let plain = buf[skipped..]
+            .iter()
+            .take_while(|&&byte| state.advance(byte))
+            .count();
+        if plain == 0 {
+            return Poll::Ready(Ok(skipped));
+        }
+        match Pin::new(&mut this.inner).poll_write(cx, &buf[skipped..skipped + plain]) {
+            Poll::Ready(Ok(written)) => Poll::Ready(Ok(skipped + written)),
+            Poll::Ready(Err(_)) | Poll::Pending if skipped > 0 => Poll::Ready(Ok(skipped)),
+            other => other,
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+/// Get the standard output of `tokio`, colorized according to `choice`.
+pub fn stdout(choice: ColorChoice) -> MaybeAnsi<::tokio::io::Stdout> {
+    MaybeAnsi::for_stream(::tokio::io::stdout(), choice, atty::Stream::Stdout)
+}
+
+/// Get the standard error of `tokio`, colorized according to `choice`.
+pub fn stderr(choice: ColorChoice) -> MaybeAnsi<::tokio::io::Stderr> {
+    MaybeAnsi::for_stream(::tokio::io::stderr(), choice, atty::Stream::Stderr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(chunks: &[&[u8]]) -> Vec<u8> {
+        let mut output = MaybeAnsi::new(Vec::new(), false);
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        for chunk in chunks {
+            let mut chunk = *chunk;
+            while !chunk.is_empty() {
+                match Pin::new(&mut output).poll_write(&mut cx, chunk) {
+                    Poll::Ready(Ok(written)) => chunk = &chunk[written..],
+                    other => panic!("unexpected {other:?}"),
+                }
+            }
+        }
+        output.into_inner()
+    }
+
+    #[test]
+    fn test_strip() {
+        assert_eq!(strip(&[b"\x1b[1;31merror\x1b[0m: oops"]), b"error: oops");
+        assert_eq!(strip(&[b"a\x1b", b"[3", b"1mb\x1b[0", b"m"]), b"ab");
+    }
+}
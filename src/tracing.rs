@@ -0,0 +1,58 @@
+/*!
+Interoperability with [`tracing-subscriber`](https://docs.rs/tracing-subscriber/latest/tracing_subscriber/).
+
+The decision whether a [`fmt`](https://docs.rs/tracing-subscriber/latest/tracing_subscriber/fmt/index.html)
+subscriber should emit ANSI escape codes can be computed with [`fmt_ansi`],
+or applied directly to the builder with [`SubscriberBuilderExt::with_color_choice`].
+
+```no_run
+use should_color::tracing::SubscriberBuilderExt;
+use should_color::{resolve, ColorChoice};
+
+# let cli_color = None;
+let color_choice = resolve(cli_color).unwrap_or(ColorChoice::Auto);
+tracing_subscriber::fmt()
+    .with_writer(std::io::stderr)
+    .with_color_choice(color_choice, atty::Stream::Stderr)
+    .init();
+```
+*/
+
+use tracing_subscriber::fmt::{format, FormatFields, SubscriberBuilder};
+
+use crate::{resolve, ColorChoice};
+
+/**
+Determine whether a `tracing_subscriber::fmt` subscriber writing to `stream` should use ANSI escape codes,
+suitable for the [`SubscriberBuilder::with_ansi`](https://docs.rs/tracing-subscriber/latest/tracing_subscriber/fmt/struct.SubscriberBuilder.html#method.with_ansi) setting.
+
+This is a convenience function equivalent to [`resolve`] without an explicit CLI preference
+and a default value of [`ColorChoice::Auto`], followed by [`ColorChoice::for_stream`].
+*/
+pub fn fmt_ansi(stream: atty::Stream) -> bool {
+    resolve(None)
+        .unwrap_or(ColorChoice::Auto)
+        .for_stream(stream)
+}
+
+/**
+Extension trait for [`SubscriberBuilder`](https://docs.rs/tracing-subscriber/latest/tracing_subscriber/fmt/struct.SubscriberBuilder.html).
+*/
+pub trait SubscriberBuilderExt: Sized {
+    /**
+    Enable or disable ANSI escape codes according to the color setting for a specific stream,
+    as determined by [`ColorChoice::for_stream`].
+
+    The `stream` should match the writer of the subscriber.
+    */
+    fn with_color_choice(self, choice: ColorChoice, stream: atty::Stream) -> Self;
+}
+
+impl<N, L, T, F, W> SubscriberBuilderExt for SubscriberBuilder<N, format::Format<L, T>, F, W>
+where
+    N: for<'writer> FormatFields<'writer> + 'static,
+{
+    fn with_color_choice(self, choice: ColorChoice, stream: atty::Stream) -> Self {
+        self.with_ansi(choice.for_stream(stream))
+    }
+}
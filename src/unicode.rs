@@ -0,0 +1,198 @@
+/*!
+Determine whether output should use Unicode glyphs, such as box-drawing characters and emoji.
+
+The design mirrors the color resolution of the crate.
+The resulting Unicode choice is determined by taking into account,
+in order of priority from higher to lower, the following settings:
+
+- `FORCE_UNICODE` environment variable,
+- explicit user preference (for instance command line arguments),
+- application default choice.
+
+If the final choice is [`UnicodeChoice::Auto`] and the feature <span class="stab portability"><code>stream</code></span> is enabled,
+the choice can be refined using [`UnicodeChoice::for_stream`], which takes into account
+the locale and the console of the output stream (see [`supports_unicode`]).
+
+# `FORCE_UNICODE`
+
+The meaning of the environment variable is the following:
+
+- if not set or `FORCE_UNICODE == ""`: ignore;
+- if set and `FORCE_UNICODE == "0"`: [`UnicodeChoice::Never`];
+- if set and `FORCE_UNICODE != ""` and `FORCE_UNICODE != "0"`: [`UnicodeChoice::Always`].
+*/
+
+/// Name of the `FORCE_UNICODE` environment variable.
+pub const FORCE_UNICODE: &str = "FORCE_UNICODE";
+
+/// Names of the locale environment variables, in order of priority.
+pub const LOCALE_VARIABLES: [&str; 3] = ["LC_ALL", "LC_CTYPE", "LANG"];
+
+/// Possible Unicode choices for the output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UnicodeChoice {
+    /// The output will contain only ASCII characters.
+    Never,
+    /// The output will contain Unicode glyphs if the locale or the console supports them.
+    Auto,
+    /// The output will contain Unicode glyphs.
+    Always,
+}
+
+#[cfg(feature = "stream")]
+impl UnicodeChoice {
+    /**
+    Determine the Unicode setting for a specific stream.
+
+    If the choice is [`UnicodeChoice::Never`] or [`UnicodeChoice::Always`],
+    the result will be `false` and `true` respectively.
+
+    If the choice is [`UnicodeChoice::Auto`], then the answer is given by [`supports_unicode`].
+    */
+    pub fn for_stream(&self, stream: atty::Stream) -> bool {
+        match self {
+            UnicodeChoice::Never => false,
+            UnicodeChoice::Always => true,
+            UnicodeChoice::Auto => supports_unicode(stream),
+        }
+    }
+}
+
+/**
+Get the setting of the `FORCE_UNICODE` environment variable.
+
+The environment variable is treated as follows:
+
+- if not set or `FORCE_UNICODE == ""`: return `None`;
+- if set and `FORCE_UNICODE == "0"`: return `Some(`[`UnicodeChoice::Never`]`)`;
+- if set and `FORCE_UNICODE != ""` and `FORCE_UNICODE != "0"`: return `Some(`[`UnicodeChoice::Always`]`)`.
+*/
+pub fn force_unicode() -> Option<UnicodeChoice> {
+    match crate::env_var(FORCE_UNICODE) {
+        Some(s) if s == "0" => Some(UnicodeChoice::Never),
+        Some(s) if !s.is_empty() => Some(UnicodeChoice::Always),
+        _ => None,
+    }
+}
+
+/**
+Determine whether the locale uses the UTF-8 encoding.
+
+The locale is given by the first non-empty variable among `LC_ALL`, `LC_CTYPE`, and `LANG`,
+and it uses UTF-8 if it contains `UTF-8` or `utf8`, case-insensitively (e.g. `en_US.UTF-8` or `C.utf8`).
+If none of the variables is set, the locale is `C`, which does not use UTF-8.
+*/
+pub fn locale_is_utf8() -> bool {
+    locale_is_utf8_with(crate::env_var)
+}
+
+/// Same as [`locale_is_utf8`], with a custom lookup of the environment variables.
+fn locale_is_utf8_with(var: impl Fn(&str) -> Option<String>) -> bool {
+    LOCALE_VARIABLES
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|value| !value.is_empty())
+        .is_some_and(|locale| {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+}
+
+/**
+Get the output code page of the console attached to the process, such as `65001` for UTF-8.
+
+Returns `None` if there is no console, or on platforms other than Windows.
+*/
+#[cfg(feature = "windows")]
+pub fn console_output_code_page() -> Option<u32> {
+    #[cfg(windows)]
+    {
+        // SAFETY: `GetConsoleOutputCP` has no preconditions, and returns 0 on failure.
+        let code_page = unsafe { windows_sys::Win32::System::Console::GetConsoleOutputCP() };
+        (code_page != 0).then_some(code_page)
+    }
+    #[cfg(not(windows))]
+    None
+}
+
+/**
+Determine whether `stream` can display Unicode glyphs.
+
+- On Windows, the result is `true` if the stream is not a console, if it is a Windows Terminal
+  (`WT_SESSION` is set), or if the output code page of the console is UTF-8
+  (see [`console_output_code_page`], which requires the <span class="stab portability"><code>windows</code></span> feature).
+- On the other platforms, the result is `true` if `TERM != "dumb"` and the locale uses UTF-8
+  (see [`locale_is_utf8`]).
+*/
+#[cfg(feature = "stream")]
+pub fn supports_unicode(stream: atty::Stream) -> bool {
+    #[cfg(windows)]
+    {
+        #[cfg(feature = "windows")]
+        let utf8_console = console_output_code_page() == Some(65001);
+        #[cfg(not(feature = "windows"))]
+        let utf8_console = false;
+        !crate::is_tty(stream)
+            || crate::env_var("WT_SESSION").is_some_and(|value| !value.is_empty())
+            || utf8_console
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = stream;
+        crate::env_var(crate::depth::TERM).as_deref() != Some("dumb") && locale_is_utf8()
+    }
+}
+
+/**
+Resolve the Unicode choice from the environment variables and an explicit CLI preference.
+
+Commonly this function will be called as `resolve_unicode(cli).unwrap_or(default)`
+to take into account a preference expressed through the CLI arguments
+and the default behavior of the application.
+*/
+pub fn resolve_unicode(cli: Option<UnicodeChoice>) -> Option<UnicodeChoice> {
+    force_unicode().or(cli)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale_from(vars: &[(&str, &str)]) -> bool {
+        locale_is_utf8_with(|name| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn test_locale_is_utf8() {
+        assert!(!locale_from(&[]));
+        assert!(locale_from(&[("LANG", "en_US.UTF-8")]));
+        assert!(locale_from(&[("LC_CTYPE", "C.utf8"), ("LANG", "C")]));
+        assert!(!locale_from(&[("LC_ALL", "C"), ("LANG", "en_US.UTF-8")]));
+        assert!(locale_from(&[("LC_ALL", ""), ("LANG", "it_IT.UTF-8")]));
+        assert!(!locale_from(&[("LANG", "POSIX")]));
+    }
+
+    #[test]
+    fn test_force_unicode() {
+        std::env::remove_var(FORCE_UNICODE);
+        assert_eq!(force_unicode(), None);
+        assert_eq!(
+            resolve_unicode(Some(UnicodeChoice::Auto)),
+            Some(UnicodeChoice::Auto)
+        );
+
+        std::env::set_var(FORCE_UNICODE, "0");
+        assert_eq!(
+            resolve_unicode(Some(UnicodeChoice::Always)),
+            Some(UnicodeChoice::Never)
+        );
+
+        std::env::set_var(FORCE_UNICODE, "1");
+        assert_eq!(force_unicode(), Some(UnicodeChoice::Always));
+        std::env::remove_var(FORCE_UNICODE);
+    }
+}
@@ -0,0 +1,107 @@
+/*!
+Detection of ANSI support in Windows consoles.
+
+Legacy Windows consoles render colors through the console API and display ANSI escape sequences verbatim,
+unless the virtual terminal processing is enabled.
+Modern terminals signal their support through environment variables,
+while users can enable the processing in all consoles by setting the registry value
+`HKEY_CURRENT_USER\Console\VirtualTerminalLevel` to `1`.
+
+When this feature is enabled, [`ColorChoice::Auto`](crate::ColorChoice::Auto) is refined on Windows by
+[`ColorChoice::for_stream`](crate::ColorChoice::for_stream) to colorize the output only if
+[`supports_ansi`] is `true`.
+*/
+
+/**
+Get the value of `HKEY_CURRENT_USER\Console\VirtualTerminalLevel`.
+
+Returns `None` if the value is not set, or on platforms other than Windows.
+*/
+pub fn virtual_terminal_level() -> Option<u32> {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::System::Registry::{
+            RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD,
+        };
+
+        let wide = |s: &str| s.encode_utf16().chain([0]).collect::<Vec<u16>>();
+        let subkey = wide("Console");
+        let value = wide("VirtualTerminalLevel");
+        let mut data: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        // SAFETY: the strings are null-terminated, and `data` is large enough for a DWORD.
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                subkey.as_ptr(),
+                value.as_ptr(),
+                RRF_RT_REG_DWORD,
+                std::ptr::null_mut(),
+                (&mut data as *mut u32).cast(),
+                &mut size,
+            )
+        };
+        (status == 0).then_some(data)
+    }
+    #[cfg(not(windows))]
+    None
+}
+
+/**
+Whether the environment variables signal a terminal which processes ANSI escape sequences.
+
+The following markers are recognized:
+
+- `WT_SESSION`, set by Windows Terminal;
+- `ConEmuANSI == "ON"`, set by ConEmu and Cmder;
+- `ANSICON`, set by ANSICON;
+- `TERM`, set by the terminals of MSYS2, Cygwin, and Git for Windows (except for `TERM == "dumb"`).
+*/
+pub fn env_supports_ansi() -> bool {
+    env_supports_ansi_with(crate::env_var)
+}
+
+/// Same as [`env_supports_ansi`], with a custom lookup of the environment variables.
+fn env_supports_ansi_with(var: impl Fn(&str) -> Option<String>) -> bool {
+    let is_set = |name| var(name).is_some_and(|value| !value.is_empty());
+    is_set("WT_SESSION")
+        || is_set("ANSICON")
+        || var("ConEmuANSI").as_deref() == Some("ON")
+        || var("TERM").is_some_and(|term| !term.is_empty() && term != "dumb")
+}
+
+/**
+Whether the Windows console processes ANSI escape sequences.
+
+This is `true` if the environment signals an ANSI terminal (see [`env_supports_ansi`])
+or if [`virtual_terminal_level`] is at least `1`.
+On platforms other than Windows it is always `true`.
+*/
+pub fn supports_ansi() -> bool {
+    !cfg!(windows)
+        || env_supports_ansi()
+        || virtual_terminal_level().is_some_and(|level| level >= 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_from(vars: &[(&str, &str)]) -> bool {
+        env_supports_ansi_with(|name| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn test_env_supports_ansi() {
+        assert!(!env_from(&[]));
+        assert!(env_from(&[("WT_SESSION", "1234")]));
+        assert!(env_from(&[("ConEmuANSI", "ON")]));
+        assert!(!env_from(&[("ConEmuANSI", "OFF")]));
+        assert!(env_from(&[("TERM", "xterm-256color")]));
+        assert!(!env_from(&[("TERM", "dumb")]));
+    }
+}
@@ -4,6 +4,8 @@
 // cargo test -- --test-threads 1
 // ```
 
+#![cfg(feature = "std")]
+
 use itertools::*;
 
 use should_color::*;
@@ -51,6 +53,14 @@ fn test_clicolor_force() {
     {
         setup_env(no_color, clicolor, clicolor_force);
         assert_eq!(resolve(cli), Some(ColorChoice::Always));
+        assert_eq!(Resolver::new().resolve(cli), Some(ColorChoice::Always));
+        assert_eq!(
+            resolve_streams(cli),
+            StreamChoices {
+                stdout: Some(ColorChoice::Always),
+                stderr: Some(ColorChoice::Always),
+            }
+        );
     }
 }
 
@@ -73,6 +83,7 @@ fn test_cli() {
     {
         setup_env(no_color, clicolor, clicolor_force);
         assert_eq!(resolve(Some(cli)), Some(cli));
+        assert_eq!(Resolver::new().resolve(Some(cli)), Some(cli));
     }
 }
 
@@ -94,5 +105,6 @@ fn test_cli() {
     {
         setup_env(no_color, clicolor, clicolor_force);
         assert_eq!(resolve(Some(cli)), Some(cli));
+        assert_eq!(Resolver::new().resolve(Some(cli)), Some(cli));
     }
 }